@@ -1,15 +1,220 @@
-use syn::{parse_str, punctuated::Pair, spanned::Spanned, Attribute, Error, LitStr, Type};
+use syn::{
+    meta::ParseNestedMeta,
+    parse::{Parse, ParseStream},
+    parse_quote, parse_str,
+    punctuated::{Pair, Punctuated},
+    spanned::Spanned,
+    Attribute, Error, Expr, Ident, LitInt, LitStr, Path, Token, Type, WherePredicate,
+};
+
+/// Parses the value of a `key = value` parameter,
+/// which may also be given as a string literal containing the value.
+fn parse_value<T: Parse>(meta: &ParseNestedMeta) -> Result<T, Error> {
+    let value = meta.value()?;
+    if value.peek(LitStr) {
+        let lit: LitStr = value.parse()?;
+        parse_str(&lit.value())
+    } else {
+        value.parse()
+    }
+}
+
+fn unknown_parameter(path: &Path) -> Error {
+    Error::new(
+        path.span(),
+        format_args!(
+            "unknown attribute parameter {}",
+            path.segments
+                .pairs()
+                .map(|pair| match pair {
+                    Pair::End(segment) => segment.ident.to_string(),
+                    Pair::Punctuated(segment, _) => format!("{}::", segment.ident),
+                })
+                .collect::<String>(),
+        ),
+    )
+}
+
+pub struct TypeData {
+    pub impl_std_traits: bool,
+    pub krate: Path,
+    pub bound: Option<Vec<WherePredicate>>,
+    pub invariant: Option<Path>,
+    pub tag_field: Option<Ident>,
+    pub universe: Option<Type>,
+    pub deny_padding: bool,
+}
+
+impl Default for TypeData {
+    fn default() -> Self {
+        Self {
+            impl_std_traits: false,
+            krate: parse_quote!(iffi),
+            bound: None,
+            invariant: None,
+            tag_field: None,
+            universe: None,
+            deny_padding: false,
+        }
+    }
+}
+
+impl TypeData {
+    pub fn from_attrs(attrs: &[Attribute]) -> Result<Self, Error> {
+        let mut data = TypeData::default();
+
+        attrs.iter().try_for_each(|attr| {
+            if attr.path().is_ident("iffi") {
+                attr.parse_nested_meta(|iffi| {
+                    if iffi.path.is_ident("impl_std_traits") {
+                        data.impl_std_traits = true;
+
+                        Ok(())
+                    } else if iffi.path.is_ident("crate") {
+                        let lit: LitStr = iffi.value()?.parse()?;
+                        data.krate = lit.parse()?;
+
+                        Ok(())
+                    } else if iffi.path.is_ident("bound") {
+                        let lit: LitStr = iffi.value()?.parse()?;
+                        let predicates = lit.parse_with(
+                            Punctuated::<WherePredicate, Token![,]>::parse_terminated,
+                        )?;
+                        data.bound = Some(predicates.into_iter().collect());
+
+                        Ok(())
+                    } else if iffi.path.is_ident("invariant") {
+                        let path: Path = parse_value(&iffi)?;
+
+                        if data.invariant.is_some() {
+                            return Err(Error::new(
+                                path.span(),
+                                "conflicting `#[iffi(invariant = ...)]` attributes",
+                            ));
+                        }
+                        data.invariant = Some(path);
+
+                        Ok(())
+                    } else if iffi.path.is_ident("universe") {
+                        let ty: Type = parse_value(&iffi)?;
+
+                        if data.universe.is_some() {
+                            return Err(Error::new(
+                                ty.span(),
+                                "conflicting `#[iffi(universe = ...)]` attributes",
+                            ));
+                        }
+                        data.universe = Some(ty);
+
+                        Ok(())
+                    } else if iffi.path.is_ident("deny_padding") {
+                        data.deny_padding = true;
+
+                        Ok(())
+                    } else if iffi.path.is_ident("tag_field") {
+                        data.tag_field = Some(parse_value(&iffi)?);
+
+                        Ok(())
+                    } else {
+                        Err(unknown_parameter(&iffi.path))
+                    }
+                })?;
+            }
+
+            Ok::<_, Error>(())
+        })?;
+
+        Ok(data)
+    }
+}
+
+#[derive(Default)]
+pub struct VariantData {}
+
+impl VariantData {
+    pub fn from_attrs(attrs: &[Attribute]) -> Result<Self, Error> {
+        let data = VariantData::default();
+
+        attrs.iter().try_for_each(|attr| {
+            if attr.path().is_ident("iffi") {
+                attr.parse_nested_meta(|iffi| {
+                    if iffi.path.is_ident("other") {
+                        // an unknown discriminant is never a valid value of a rust enum,
+                        // so there is nothing it could be transmuted into.
+                        Err(Error::new(
+                            iffi.path.span(),
+                            "catch-all variants cannot be transmuted into; \
+                            model open C enums as a `#[repr(transparent)]` newtype \
+                            over the tag with an associated constant for each known value",
+                        ))
+                    } else if iffi.path.is_ident("discriminant_range") {
+                        // likewise, a variant has exactly one discriminant
+                        // and has nowhere to store the value it was converted from.
+                        Err(Error::new(
+                            iffi.path.span(),
+                            "variants cannot span a range of discriminants; \
+                            model the tag as a `#[repr(transparent)]` newtype \
+                            and validate the range with `#[iffi(check = ...)]`",
+                        ))
+                    } else {
+                        Err(unknown_parameter(&iffi.path))
+                    }
+                })?;
+            }
+
+            Ok::<_, Error>(())
+        })?;
+
+        Ok(data)
+    }
+}
+
+/// An inclusive range of bit indices written as `lo..=hi`, `lo..hi` or a single `bit`.
+struct BitRange(u32, u32);
+
+impl Parse for BitRange {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let lo: LitInt = input.parse()?;
+        let lo = lo.base10_parse()?;
+        let hi = if input.peek(Token![..=]) {
+            input.parse::<Token![..=]>()?;
+            input.parse::<LitInt>()?.base10_parse()?
+        } else if input.peek(Token![..]) {
+            input.parse::<Token![..]>()?;
+            let hi: LitInt = input.parse()?;
+            hi.base10_parse::<u32>()?
+                .checked_sub(1)
+                .ok_or_else(|| Error::new(hi.span(), "expected a non-empty range of bits"))?
+        } else {
+            lo
+        };
+
+        if hi < lo {
+            return Err(input.error("expected a non-empty range of bits"));
+        }
+        Ok(Self(lo, hi))
+    }
+}
 
 #[derive(Default)]
 pub enum Superset {
     #[default]
     Default,
-    Type(Type),
+    Type(Box<Type>),
 }
 
 #[derive(Default)]
 pub struct FieldData {
     pub superset: Superset,
+    pub check: Option<Path>,
+    pub assume_valid: bool,
+    /// The union members selected by each value of the struct's tag field.
+    pub payload: Option<Vec<(Ident, Expr)>>,
+    /// The lowest and highest meaningful bits of an integer field.
+    pub bits: Option<(u32, u32)>,
+    pub ignore_reserved_bits: bool,
+    /// The integer field holding the number of elements behind this pointer field.
+    pub len: Option<Ident>,
 }
 
 impl FieldData {
@@ -20,35 +225,82 @@ impl FieldData {
             if attr.path().is_ident("iffi") {
                 attr.parse_nested_meta(|iffi| {
                     if iffi.path.is_ident("with") {
-                        let lit: LitStr = iffi.value()?.parse()?;
-                        let ty: Type = parse_str(&lit.value())?;
+                        let ty: Type = parse_value(&iffi)?;
 
                         if let Superset::Default = data.superset {
-                            data.superset = Superset::Type(ty);
+                            data.superset = Superset::Type(Box::new(ty));
                         } else {
                             return Err(Error::new(
-                                lit.span(),
-                                "conflicting `#[iffi(with = \"...\")]` attributes",
+                                ty.span(),
+                                "conflicting `#[iffi(with = ...)]` attributes",
                             ));
                         }
 
                         Ok(())
-                    } else {
+                    } else if iffi.path.is_ident("check") {
+                        let path: Path = parse_value(&iffi)?;
+
+                        if data.check.is_some() {
+                            return Err(Error::new(
+                                path.span(),
+                                "conflicting `#[iffi(check = ...)]` attributes",
+                            ));
+                        }
+                        data.check = Some(path);
+
+                        Ok(())
+                    } else if iffi.path.is_ident("payload") {
+                        let mut members = Vec::new();
+                        iffi.parse_nested_meta(|member| {
+                            let ident = member.path.require_ident()?.clone();
+                            let tag: Expr = parse_value(&member)?;
+                            members.push((ident, tag));
+
+                            Ok(())
+                        })?;
+                        data.payload = Some(members);
+
+                        Ok(())
+                    } else if iffi.path.is_ident("bits") {
+                        let BitRange(lo, hi) = parse_value(&iffi)?;
+                        data.bits = Some((lo, hi));
+
+                        Ok(())
+                    } else if iffi.path.is_ident("len") {
+                        data.len = Some(parse_value(&iffi)?);
+
+                        Ok(())
+                    } else if iffi.path.is_ident("reserved_bits") {
+                        let mode: Ident = parse_value(&iffi)?;
+                        if mode == "ignore" {
+                            data.ignore_reserved_bits = true;
+                        } else if mode == "zero" {
+                            data.ignore_reserved_bits = false;
+                        } else {
+                            return Err(Error::new(
+                                mode.span(),
+                                "expected `reserved_bits = zero` or `reserved_bits = ignore`",
+                            ));
+                        }
+
+                        Ok(())
+                    } else if iffi.path.is_ident("unsafe") {
+                        iffi.parse_nested_meta(|unsafe_meta| {
+                            if unsafe_meta.path.is_ident("assume_valid") {
+                                data.assume_valid = true;
+
+                                Ok(())
+                            } else {
+                                Err(unknown_parameter(&unsafe_meta.path))
+                            }
+                        })
+                    } else if iffi.path.is_ident("assume_valid") {
                         Err(Error::new(
                             iffi.path.span(),
-                            format_args!(
-                                "unknown attribute parameter {}",
-                                iffi.path
-                                    .segments
-                                    .pairs()
-                                    .map(|pair| match pair {
-                                        Pair::End(segment) => segment.ident.to_string(),
-                                        Pair::Punctuated(segment, _) =>
-                                            format!("{}::", segment.ident),
-                                    })
-                                    .collect::<String>(),
-                            ),
+                            "`assume_valid` is unsafe, use `#[iffi(unsafe(assume_valid))]`",
                         ))
+                    } else {
+                        Err(unknown_parameter(&iffi.path))
                     }
                 })?;
             }
@@ -0,0 +1,47 @@
+use proc_macro2::{Ident, TokenStream, TokenTree};
+use quote::{quote, ToTokens};
+use syn::{parse_quote, Generics, Path, Type, WherePredicate};
+
+fn mentions_any(tokens: TokenStream, params: &[&Ident]) -> bool {
+    tokens.into_iter().any(|tt| match tt {
+        TokenTree::Ident(ident) => params.contains(&&ident),
+        TokenTree::Group(group) => mentions_any(group.stream(), params),
+        _ => false,
+    })
+}
+
+/// Whether `ty` mentions any of the type parameters declared in `generics`.
+pub fn is_generic(ty: &Type, generics: &Generics) -> bool {
+    let params: Vec<_> = generics.type_params().map(|param| &param.ident).collect();
+    !params.is_empty() && mentions_any(ty.to_token_stream(), &params)
+}
+
+/// Builds a `Generics` with an extra where-clause predicate
+/// for every `(bounded, bound)` pair whose type mentions a type parameter.
+pub fn with_bounds(
+    generics: &Generics,
+    bounds: impl IntoIterator<Item = (Type, TokenStream)>,
+) -> Generics {
+    let predicates: Vec<WherePredicate> = bounds
+        .into_iter()
+        .filter(|(ty, _)| is_generic(ty, generics))
+        .map(|(ty, bound)| parse_quote!(#ty: #bound))
+        .collect();
+
+    with_predicates(generics, &predicates)
+}
+
+/// Builds a `Generics` with the given extra where-clause predicates.
+pub fn with_predicates(generics: &Generics, predicates: &[WherePredicate]) -> Generics {
+    let mut generics = generics.clone();
+    generics
+        .make_where_clause()
+        .predicates
+        .extend(predicates.iter().cloned());
+    generics
+}
+
+/// The `Iffi` bound required for a field validated over `proxy_ty`.
+pub fn iffi_bound(proxy_ty: &Type, krate: &Path) -> TokenStream {
+    quote!(#krate::Iffi<#proxy_ty>)
+}
@@ -1,30 +1,128 @@
 use proc_macro2::{Ident, Span, TokenStream};
-use quote::{quote, ToTokens};
+use quote::{format_ident, quote, ToTokens};
 use syn::{
-    parenthesized, parse_macro_input, parse_quote,
+    parse_macro_input, parse_quote,
     punctuated::{Pair, Punctuated},
     spanned::Spanned,
-    Data, DeriveInput, Error, Expr, Field, FieldMutability, Fields, FieldsNamed, FieldsUnnamed,
-    Index, LitInt, Meta, Token, Type, Visibility,
+    Data, DeriveInput, Error, Field, FieldMutability, Fields, FieldsNamed, FieldsUnnamed, Index,
+    Path, Type, Visibility,
 };
 
+mod bounds;
+mod mirror;
 mod nicheless;
+mod one_niche;
+mod repr;
 use nicheless::{Item, ItemData};
+use repr::{Repr, MISSING_REPR_ERR};
 
-use crate::attrs::{FieldData, Superset};
+use crate::attrs::{FieldData, Superset, TypeData, VariantData};
 mod attrs;
 
-#[proc_macro_derive(Nicheless)]
+/// Derives `Nicheless` for types whose fields are all nicheless.
+///
+/// Structs and unions must be `#[repr(C)]` or `#[repr(transparent)]`
+/// so that their layout is FFI-safe.
+///
+/// Enums must be `#[repr(u8)]`, `#[repr(i8)]`, `#[repr(u16)]` or `#[repr(i16)]`
+/// and have a variant for every value of their tag.
+///
+/// Unions are nicheless if every one of their fields is, whichever one is active.
+///
+/// Accepts the `#[iffi(crate = "path::to::iffi")]` type attribute, see the `Iffi` derive.
+#[proc_macro_derive(Nicheless, attributes(iffi))]
 pub fn derive_nicheless(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let derive_input = parse_macro_input!(input as DeriveInput);
-    let result = derive_input
-        .try_into()
-        .and_then(|i| nicheless::impl_nicheless(&i))
+    let result = TypeData::from_attrs(&derive_input.attrs)
+        .and_then(|data| {
+            let attrs = derive_input.attrs.clone();
+            let item = derive_input.try_into()?;
+            nicheless::impl_nicheless(&item, &attrs, &data.krate)
+        })
         .unwrap_or_else(|e| e.to_compile_error());
 
     result.into()
 }
 
+/// Derives `Iffi` over `MaybeInvalid<Self>` for `#[repr(C)]`, `#[repr(transparent)]`
+/// and primitive-represented types.
+///
+/// Every field whose type mentions a generic type parameter
+/// gets an `Iffi` bound over its universe in the generated impl.
+///
+/// Enums must be `#[repr(Int)]`, `#[repr(C, Int)]` or `#[repr(C)]`, in which case
+/// the tag has the type of the platform's C enums (`core::ffi::c_int`).
+/// `#[repr(C)]` and `#[repr(C, Int)]` enums use the `(tag, union)` layout
+/// that Rust guarantees for those representations.
+///
+/// Unions are supported if all of their fields are `Nicheless`,
+/// in which case every value of the union is valid.
+///
+/// `#[repr(packed)]` and `#[repr(packed(N))]` structs are supported;
+/// their fields are copied out with unaligned reads before being validated.
+///
+/// Errors from each field are annotated with the field's name as they propagate,
+/// so that the error's path reads like `Packet.Data.header.flags`.
+///
+/// The helper types that model the layout of the type during validation
+/// are asserted at compile time to have the same size and alignment as it.
+///
+/// # Type attributes
+/// * `#[iffi(impl_std_traits)]`: also implement `TryFrom<MaybeInvalid<Self>>` for the type
+///   and `From<Self>` for `MaybeInvalid<Self>`.
+/// * `#[iffi(bound = "T: Iffi, U: Nicheless")]`: replace the inferred bounds of the generated
+///   impl with the given where-clause predicates.
+/// * `#[iffi(invariant = path::to::invariant)]`: after every field is validated, also call
+///   `fn invariant(&Self) -> Result<(), iffi::ErrorKind>` to check relationships between fields.
+/// * `#[iffi(universe = RawType)]`: implement `Iffi<RawType>` for an existing nicheless struct
+///   instead of `Iffi<MaybeInvalid<Self>>`, matching fields by name or position.
+///   The layouts of the two structs and the offsets of their fields are asserted to be equal.
+/// * `#[iffi(tag_field = field)]`: the integer field of a struct that selects
+///   the active member of the union field marked with `#[iffi(payload(...))]`.
+/// * `#[iffi(deny_padding)]`: also implement `NoPadding` for a struct,
+///   asserting at compile time that its fields have no padding and none lies between them.
+/// * `#[iffi(crate = "path::to::iffi")]`: the path to the `iffi` crate used in the generated code,
+///   for when it is re-exported by another crate.
+///
+/// # Field attributes
+/// * `#[iffi(with = Type)]` or `#[iffi(with = "Type")]`: validate the field
+///   over the universe `Type` instead of `MaybeInvalid<FieldType>`.
+///   `Type` is asserted to have the same size and alignment as the field.
+/// * `#[iffi(check = path::to::check)]`: after the field is validated, also call
+///   `fn check(&Universe) -> Result<(), iffi::ErrorKind>` with the field's universe value.
+/// * `#[iffi(payload(member = TAG, ...))]`: validate only the member of this union field
+///   whose tag expression equals the value of the type's `tag_field`.
+///   Any other tag value is an invalid discriminant.
+/// * `#[iffi(bits = 0..=4)]`: only the given bits of this integer field are meaningful
+///   and every other bit must be zero.
+///   Add `reserved_bits = ignore` to accept any value in the other bits instead.
+/// * `#[iffi(len = field)]`: this raw pointer field points to as many elements as the
+///   integer `field` holds, so it must be aligned and cannot be null unless the length is zero.
+/// * `#[iffi(unsafe(assume_valid))]`: skip validating the field entirely.
+///
+/// # Open enums
+/// C APIs often reserve the right to add new enumerators, but a Rust enum cannot hold
+/// a discriminant it does not declare, so there is no catch-all variant attribute.
+/// Model such enums as a `#[repr(transparent)]` newtype over the tag instead:
+/// ```ignore
+/// #[derive(Iffi, Clone, Copy, PartialEq, Eq)]
+/// #[repr(transparent)]
+/// pub struct Status(u32);
+///
+/// impl Status {
+///     pub const OK: Self = Self(0);
+///     pub const BUSY: Self = Self(1);
+/// }
+/// ```
+///
+/// For the same reason, a variant cannot stand for a range of discriminants.
+/// Validate the newtype's field with `#[iffi(check = ...)]` to accept only the documented ranges.
+///
+/// # Safety
+/// `#[iffi(unsafe(assume_valid))]` makes the caller responsible for ensuring that
+/// every universe value ever converted holds a valid value in that field,
+/// for example because it was already validated out-of-band.
+/// Converting a value with an invalid field is undefined behaviour.
 #[proc_macro_derive(Iffi, attributes(iffi))]
 pub fn derive_iffi(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let derive_input = parse_macro_input!(input as DeriveInput);
@@ -33,13 +131,76 @@ pub fn derive_iffi(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     result.into()
 }
 
-fn fields_def(fields: &Fields) -> Result<Fields, Error> {
-    fn field_def(field: &Field) -> Result<Field, Error> {
+/// Derives `OneNiche` for `#[repr(transparent)]` newtypes over a type with exactly one niche.
+///
+/// The niche must be declared with `#[one_niche(value = ...)]`, the integer value
+/// of the bit-pattern that `None` takes in `Option<Self>`.
+/// For types without generic parameters, it is checked against the inner type at compile time.
+///
+/// Accepts the `#[iffi(crate = "path::to::iffi")]` type attribute, see the `Iffi` derive.
+///
+/// ```ignore
+/// #[derive(OneNiche)]
+/// #[repr(transparent)]
+/// #[one_niche(value = 0)]
+/// pub struct Handle(NonZeroU32);
+/// ```
+#[proc_macro_derive(OneNiche, attributes(one_niche, iffi))]
+pub fn derive_one_niche(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let derive_input = parse_macro_input!(input as DeriveInput);
+    let result = TypeData::from_attrs(&derive_input.attrs)
+        .and_then(|data| one_niche::impl_one_niche(&derive_input, &data.krate))
+        .unwrap_or_else(|e| e.to_compile_error());
+
+    result.into()
+}
+
+/// Generates a nicheless mirror of a struct to use as its universe.
+///
+/// `#[iffi::mirror(pub RawFoo)]` on a struct `Foo` generates a struct `RawFoo`
+/// with the same representation whose fields have the universe types of the fields of `Foo`,
+/// `MaybeInvalid<FieldType>` by default, and implements `Nicheless` for it.
+/// `Foo` then implements `Iffi<RawFoo>`, `TryFrom<RawFoo>`, and `RawFoo` implements `From<Foo>`.
+///
+/// The field attributes of the `Iffi` derive are supported,
+/// as are the `tag_field`, `invariant`, `deny_padding` and `crate` type attributes.
+/// `bound` and `universe` are rejected, as is `impl_std_traits` unless the struct also derives `Iffi`.
+/// The attributes are kept for the `Iffi` derive if the struct also derives it.
+///
+/// ```ignore
+/// #[iffi::mirror(pub RawPoint)]
+/// #[repr(C)]
+/// pub struct Point {
+///     pub x: NonZeroU32,
+///     #[iffi(with = u32)]
+///     pub y: NonZeroU32,
+/// }
+/// // generates:
+/// #[repr(C)]
+/// pub struct RawPoint {
+///     pub x: MaybeInvalid<NonZeroU32>,
+///     pub y: u32,
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn mirror(
+    args: proc_macro::TokenStream,
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    let args = parse_macro_input!(args as mirror::MirrorArgs);
+    let input = parse_macro_input!(input as DeriveInput);
+    let result = mirror::impl_mirror(args, input).unwrap_or_else(|e| e.to_compile_error());
+
+    result.into()
+}
+
+fn fields_def(fields: &Fields, krate: &Path) -> Result<Fields, Error> {
+    fn field_def(field: &Field, krate: &Path) -> Result<Field, Error> {
         let ty = &field.ty;
         let data = FieldData::from_attrs(&field.attrs)?;
         let superset = match data.superset {
             Superset::Default => quote! {
-                iffi::MaybeInvalid<#ty>
+                #krate::MaybeInvalid<#ty>
             },
             Superset::Type(ty) => ty.to_token_stream(),
         };
@@ -60,9 +221,9 @@ fn fields_def(fields: &Fields) -> Result<Fields, Error> {
             let fields: Punctuated<Field, _> = named
                 .pairs()
                 .map(|pair| match pair {
-                    Pair::End(value) => field_def(value).map(Pair::End),
+                    Pair::End(value) => field_def(value, krate).map(Pair::End),
                     Pair::Punctuated(value, &punct) => {
-                        Ok(Pair::Punctuated(field_def(value)?, punct))
+                        Ok(Pair::Punctuated(field_def(value, krate)?, punct))
                     }
                 })
                 .collect::<Result<_, _>>()?;
@@ -78,9 +239,9 @@ fn fields_def(fields: &Fields) -> Result<Fields, Error> {
             let fields: Punctuated<Field, _> = unnamed
                 .pairs()
                 .map(|pair| match pair {
-                    Pair::End(value) => field_def(value).map(Pair::End),
+                    Pair::End(value) => field_def(value, krate).map(Pair::End),
                     Pair::Punctuated(value, &punct) => {
-                        Ok(Pair::Punctuated(field_def(value)?, punct))
+                        Ok(Pair::Punctuated(field_def(value, krate)?, punct))
                     }
                 })
                 .collect::<Result<_, _>>()?;
@@ -92,14 +253,26 @@ fn fields_def(fields: &Fields) -> Result<Fields, Error> {
     }
 }
 
+/// Checks each field, returning the first error.
+///
+/// If `each` is set, the generated code instead passes every error to `report`
+/// for `Iffi::can_transmute_each`.
 fn fields_check<'a>(
     proxy_fields: impl Iterator<Item = &'a Field>,
     real_fields: impl Iterator<Item = &'a Field>,
+    variant: Option<&Ident>,
+    krate: &Path,
+    packed: bool,
+    each: bool,
 ) -> Result<TokenStream, Error> {
     fn field_check(
         i: usize,
         proxy_field: &Field,
         real_field: &Field,
+        variant: Option<&Ident>,
+        krate: &Path,
+        packed: bool,
+        each: bool,
     ) -> Result<TokenStream, Error> {
         let index = &Index::from(i);
         let access = proxy_field
@@ -108,56 +281,361 @@ fn fields_check<'a>(
             .map_or_else(|| index.to_token_stream(), |ident| ident.to_token_stream());
         let proxy_ty = &proxy_field.ty;
         let real_ty = &real_field.ty;
+        let data = FieldData::from_attrs(&real_field.attrs)?;
+
+        // fields of packed structs may be unaligned so cannot be borrowed in place.
+        let (read, value) = if packed {
+            let read = quote! {
+                // SAFETY: the pointer is valid for reads and the universe is nicheless.
+                let field = ::core::mem::ManuallyDrop::new(unsafe {
+                    ::core::ptr::read_unaligned(::core::ptr::addr_of!(superset.#access))
+                });
+            };
+            (Some(read), quote!(&*field))
+        } else {
+            (None, quote!(&superset.#access))
+        };
+
+        // a mis-sized superset would shift the offsets of every following field.
+        let layout = matches!(data.superset, Superset::Type(_)).then(|| {
+            let size_err =
+                format!("the universe of field `{access}` must have the same size as the field");
+            let align_err = format!(
+                "the universe of field `{access}` must have the same alignment as the field"
+            );
+            quote! {
+                const {
+                    assert!(
+                        ::core::mem::size_of::<#proxy_ty>() == ::core::mem::size_of::<#real_ty>(),
+                        #size_err,
+                    );
+                    assert!(
+                        ::core::mem::align_of::<#proxy_ty>() == ::core::mem::align_of::<#real_ty>(),
+                        #align_err,
+                    );
+                }
+            }
+        });
+
+        let name = real_field
+            .ident
+            .as_ref()
+            .map_or_else(|| i.to_string(), ToString::to_string);
+        let name = match variant {
+            Some(variant) => format!("{variant}.{name}"),
+            None => name,
+        };
+        // the universe is inferred from the field so it need not be named.
+        let error = quote! {
+            |kind| field_error::<#real_ty, _>(#value)(kind).in_field::<Self>(#name)
+        };
+
+        // payloads are checked by `payload_check` once the tag is known.
+        let structural = (!data.assume_valid && data.payload.is_none()).then(|| {
+            if each {
+                quote! {
+                    <#real_ty as #krate::Iffi<#proxy_ty>>::can_transmute_each(
+                        #value,
+                        &mut |error| report(error.in_field::<Self>(#name)),
+                    );
+                }
+            } else {
+                quote! {
+                    <#real_ty as #krate::Iffi<#proxy_ty>>::can_transmute(#value)
+                        .map_err(|error| error.in_field::<Self>(#name))?;
+                }
+            }
+        });
+        let check = data.check.map(|check| {
+            quote! {
+                #check(#value)
+                    .map_err(#error)?;
+            }
+        });
+        let bits = data
+            .bits
+            .filter(|_| !data.ignore_reserved_bits)
+            .map(|(lo, hi)| {
+                let (lo, hi) = (Index::from(lo as usize), Index::from(hi as usize));
+                quote! {
+                    const MASK: #real_ty = {
+                        let mut mask: #real_ty = 0;
+                        let mut bit = #lo;
+                        while bit <= #hi {
+                            mask |= 1 << bit;
+                            bit += 1;
+                        }
+                        mask
+                    };
+                    // SAFETY: integers are valid for any bit-pattern.
+                    let bits = unsafe { *(#value as *const _ as *const #real_ty) };
+                    if bits & !MASK != 0 {
+                        return Err((#error)(
+                            #krate::ErrorKind::ReservedBits {
+                                bits: #krate::BitPattern::from_ne(&bits),
+                                mask: #krate::BitPattern::from_ne(&MASK),
+                            },
+                        ));
+                    }
+                }
+            });
+        let len = data.len.map(|len| {
+            // the fields of enum variants are renamed like `sanitize_fields`.
+            let len = match variant {
+                Some(_) => Ident::new(&format!("field_{len}"), len.span()),
+                None => len,
+            };
+            quote! {
+                // the size is taken from the pointer type, as the field may be unaligned.
+                fn pointee_size<P>(_: *const P) -> usize {
+                    ::core::mem::size_of::<P>()
+                }
+
+                let len = ::core::ptr::addr_of!(superset.#len);
+                // SAFETY: the length field is an initialized integer.
+                let len = unsafe {
+                    ::core::slice::from_raw_parts(len as *const u8, pointee_size(len))
+                };
+                // SAFETY: raw pointers are valid for any bit-pattern.
+                let ptr = unsafe { *(#value as *const _ as *const #real_ty) };
+                if ptr.is_null() {
+                    if len.iter().any(|&byte| byte != 0) {
+                        return Err((#error)(
+                            #krate::ErrorKind::NullPtr,
+                        ));
+                    }
+                } else if !ptr.is_aligned() {
+                    fn pointee_align<P>(_: *const P) -> usize {
+                        ::core::mem::align_of::<P>()
+                    }
+
+                    let addr = ptr as usize;
+                    return Err((#error)(
+                        #krate::ErrorKind::Misaligned {
+                            required: pointee_align(ptr),
+                            // the lowest set bit of the address.
+                            found: addr & addr.wrapping_neg(),
+                        },
+                    ));
+                }
+            }
+        });
+        // the remaining checks stop at their first error, which is reported on its own.
+        let rest = if each && (check.is_some() || bits.is_some() || len.is_some()) {
+            Some(quote! {
+                let rest = || -> Result<(), #krate::Error> {
+                    #check
+                    #bits
+                    #len
+                    Ok(())
+                };
+                if let Err(error) = rest() {
+                    report(error);
+                }
+            })
+        } else if each {
+            None
+        } else {
+            Some(quote! {
+                #check
+                #bits
+                #len
+            })
+        };
         Ok(quote! {
-            <#real_ty as iffi::Iffi<#proxy_ty>>::can_transmute(&superset.#access)?
+            {
+                #layout
+                #read
+                #structural
+                #rest
+            }
         })
     }
     let mut field = Vec::new();
     proxy_fields
         .zip(real_fields)
         .enumerate()
-        .map(|(i, (proxy_field, real_field))| field_check(i, proxy_field, real_field))
+        .map(|(i, (proxy_field, real_field))| {
+            field_check(i, proxy_field, real_field, variant, krate, packed, each)
+        })
         .try_for_each(|x| x.map(|x| field.push(x)))?;
+    let ok = (!each).then(|| quote!(Ok(())));
     Ok(quote! {
-        #( #field; )*
+        #[allow(dead_code)]
+        fn field_error<T: #krate::Iffi<U>, U: #krate::Nicheless>(
+            _: &U,
+        ) -> fn(#krate::ErrorKind) -> #krate::Error {
+            #krate::Error::new::<T, U>
+        }
+
+        #( #field )*
 
-        Ok(())
+        #ok
     })
 }
 
-struct Repr {
-    layout: LayoutRepr,
-    align: Option<usize>,
-}
-enum LayoutRepr {
-    C,
-    Transparent,
+/// Checks the active member of a union field selected by the struct's tag field.
+fn payload_check(
+    fields: &Fields,
+    tag_field: &Ident,
+    universe: &TokenStream,
+    packed: bool,
+    krate: &Path,
+) -> Result<TokenStream, Error> {
+    let Some(tag) = fields
+        .iter()
+        .find(|field| field.ident.as_ref() == Some(tag_field))
+    else {
+        return Err(Error::new(tag_field.span(), "no field with this name"));
+    };
+    let mut payloads = fields.iter().filter_map(|field| {
+        FieldData::from_attrs(&field.attrs)
+            .map(|data| data.payload.map(|members| (field, members)))
+            .transpose()
+    });
+    let (payload, members) = payloads.next().ok_or_else(|| {
+        Error::new(
+            tag_field.span(),
+            "`#[iffi(tag_field = ...)]` requires a field with `#[iffi(payload(...))]`",
+        )
+    })??;
+    if let Some(other) = payloads.next() {
+        let (other, _) = other?;
+        return Err(Error::new(
+            other.span(),
+            "only one field can be selected by the tag field",
+        ));
+    }
+    if packed {
+        return Err(Error::new(
+            tag_field.span(),
+            "`#[iffi(tag_field = ...)]` is not supported on `#[repr(packed)]` types",
+        ));
+    }
+
+    let tag_ty = &tag.ty;
+    let payload_access = &payload.ident;
+    let payload_ty = &payload.ty;
+    let (members, tags): (Vec<_>, Vec<_>) = members.into_iter().unzip();
+    let member_names = members
+        .iter()
+        .map(|member| format!("{}.{member}", payload_access.as_ref().unwrap()));
+    let tag_name = tag_field.to_string();
+
+    Ok(quote! {
+        {
+            fn member<M: #krate::Iffi>(member: *const M) -> Result<(), #krate::Error> {
+                // SAFETY: `MaybeInvalid<M>` has the same layout as `M`.
+                M::can_transmute(unsafe { &*(member as *const #krate::MaybeInvalid<M>) })
+            }
+
+            // SAFETY: tag fields are integers, which are valid for any bit-pattern.
+            let tag = unsafe {
+                ::core::ptr::read(::core::ptr::addr_of!(superset.#tag_field) as *const #tag_ty)
+            };
+            let payload = ::core::ptr::addr_of!(superset.#payload_access) as *const #payload_ty;
+
+            #(
+                if tag == (#tags) {
+                    // SAFETY: the pointer is valid and is never read through.
+                    member(unsafe { ::core::ptr::addr_of!((*payload).#members) })
+                        .map_err(|error| error.in_field::<Self>(#member_names))?;
+                } else
+            )* {
+                return Err(#krate::Error::new::<Self, #universe>(
+                    #krate::ErrorKind::InvalidEnumDiscriminant {
+                        ty: ::core::any::type_name::<Self>(),
+                        bits: #krate::BitPattern::from_le(&tag.to_le_bytes()),
+                        valid: #krate::BitRanges::from_ne_values::<#tag_ty>(
+                            &[#((#tags)),*],
+                            |tag| tag.checked_add(1),
+                        ),
+                    },
+                ).in_field::<Self>(#tag_name));
+            }
+        }
+    })
 }
 
-impl ToTokens for Repr {
-    fn to_tokens(&self, tokens: &mut TokenStream) {
-        let layout = match &self.layout {
-            LayoutRepr::C => quote!(C),
-            LayoutRepr::Transparent => quote!(transparent),
-        };
-        let align = self.align.map(|n| {
-            let align = Index::from(n).into_token_stream();
-            quote!(align(#align))
-        });
-        let mut list = Vec::new();
-        list.push(layout);
-        list.extend(align);
-
-        tokens.extend(quote! {
-            #[repr(
-                #(#list),*
-            )]
+fn fields_trivial<'a>(
+    proxy_fields: impl Iterator<Item = &'a Field>,
+    real_fields: impl Iterator<Item = &'a Field>,
+    krate: &Path,
+) -> Result<TokenStream, Error> {
+    let trivial = proxy_fields
+        .zip(real_fields)
+        .map(|(proxy_field, real_field)| {
+            let proxy_ty = &proxy_field.ty;
+            let real_ty = &real_field.ty;
+            let data = FieldData::from_attrs(&real_field.attrs)?;
+            let bits = data.bits.is_some() && !data.ignore_reserved_bits;
+            let trivial = data.check.is_none() && data.payload.is_none() && data.len.is_none();
+            Ok(if !trivial || bits {
+                quote!(false)
+            } else if data.assume_valid {
+                quote!(true)
+            } else {
+                quote! {
+                    <#real_ty as #krate::Iffi<#proxy_ty>>::TRIVIAL_CHECK
+                }
+            })
         })
+        .collect::<Result<Vec<_>, Error>>()?;
+    Ok(quote! {
+        true #( && #trivial )*
+    })
+}
+
+/// Adds a `PhantomData` of the input type to the fields of a helper item
+/// so that every generic parameter is used, even if a field's universe does not mention it.
+fn push_marker(fields: &mut Fields, input: &DeriveInput) {
+    if input.generics.params.is_empty() {
+        return;
+    }
+
+    let ident = &input.ident;
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    let mut marker = Field {
+        attrs: Vec::new(),
+        colon_token: None,
+        ident: None,
+        mutability: FieldMutability::None,
+        ty: Type::Verbatim(quote! {
+            ::core::marker::PhantomData<#ident #ty_generics>
+        }),
+        vis: Visibility::Inherited,
+    };
+
+    match fields {
+        Fields::Named(FieldsNamed { named, .. }) => {
+            marker.colon_token = Some(Default::default());
+            marker.ident = Some(Ident::new("__iffi_marker", Span::call_site()));
+            named.push(marker);
+        }
+        Fields::Unnamed(FieldsUnnamed { unnamed, .. }) => unnamed.push(marker),
+        Fields::Unit => (),
     }
 }
 
-const MISSING_REPR_ERR: &str = "Expected type to be `#[repr(C)]` or `#[repr(transparent)]`";
-const UNION_ERR: &str = "Iffi does not support unions!";
+/// Asserts that a generated helper type models the layout of `Self`,
+/// so that transmuting between references to them is sound.
+fn layout_assertion(helper: &TokenStream, name: &str) -> TokenStream {
+    let size_err = format!("the generated `{name}` type must have the same size as `Self`");
+    let align_err = format!("the generated `{name}` type must have the same alignment as `Self`");
+
+    quote! {
+        const {
+            assert!(
+                ::core::mem::size_of::<#helper>() == ::core::mem::size_of::<Self>(),
+                #size_err,
+            );
+            assert!(
+                ::core::mem::align_of::<#helper>() == ::core::mem::align_of::<Self>(),
+                #align_err,
+            );
+        }
+    }
+}
 
 fn sanitize_fields(fields: &Fields) -> Fields {
     match fields {
@@ -194,65 +672,165 @@ fn sanitize_fields(fields: &Fields) -> Fields {
 }
 
 fn impl_iffi(input: DeriveInput) -> Result<TokenStream, Error> {
-    let mut repr = None;
-    let mut enum_repr = None;
-    let mut repr_align = None;
-    let mut attrs = Vec::new();
-
-    input.attrs.iter().try_for_each(|attr| {
-        if attr.path().is_ident("repr") {
-            attr.parse_nested_meta(|meta| {
-                if meta.path.is_ident("C") {
-                    repr = Some(LayoutRepr::C);
-                } else if meta.path.is_ident("transparent") {
-                    repr = Some(LayoutRepr::Transparent);
-                } else if meta.path.is_ident("align") {
-                    let content;
-                    parenthesized!(content in meta.input);
-                    let lit: LitInt = content.parse()?;
-                    repr_align = Some(lit.base10_parse()?);
-                } else if meta.path.is_ident("packed") {
-                    return Err(Error::new(
-                        meta.path.span(),
-                        "Iffi does not support `#[repr(packed)]`!",
-                    ));
-                } else if let Some(ident) = meta.path.get_ident().map(ToString::to_string) {
-                    match ident.as_str() {
-                        "u8" | "u16" | "u32" | "u64" | "u128" | "usize" | "i8" | "i16" | "i32"
-                        | "i64" | "i128" | "isize" => {
-                            let ident = Ident::new(&ident, meta.path.span());
-                            repr = Some(LayoutRepr::C);
-                            enum_repr = Some(ident);
-                        }
-                        _ => (),
-                    }
-                }
+    let repr = Repr::from_attrs(&input.attrs)?
+        .ok_or_else(|| Error::new(Span::call_site(), MISSING_REPR_ERR))?;
+    let type_data = TypeData::from_attrs(&input.attrs)?;
+    let krate = &type_data.krate;
 
-                Ok(())
-            })?;
-        } else if attr.path().is_ident("iffi_attr") {
-            let nested = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+    let ident = &input.ident;
+    let mut trivial_check = quote!(false);
+    let mut field_bounds = Vec::new();
 
-            attrs.extend(nested);
+    if let Some(universe) = &type_data.universe {
+        if !input.generics.params.is_empty() {
+            return Err(Error::new(
+                input.generics.span(),
+                "`#[iffi(universe = ...)]` does not support generic types",
+            ));
         }
+        if !matches!(input.data, Data::Struct(_)) {
+            return Err(Error::new(
+                universe.span(),
+                "`#[iffi(universe = ...)]` only supports structs",
+            ));
+        }
+    }
 
-        Ok::<_, Error>(())
-    })?;
-
-    let repr = repr.ok_or_else(|| Error::new(Span::call_site(), MISSING_REPR_ERR))?;
-
-    let repr = Repr {
-        layout: repr,
-        align: repr_align,
-    };
+    // only structs check their fields independently of one another,
+    // so other types report just the first error of `can_transmute`.
+    let mut each_expr = None;
+    let mut fields_expr = None;
+    let check_expr = match &input.data {
+        Data::Struct(data) if type_data.universe.is_some() => {
+            let universe = type_data.universe.as_ref().unwrap();
+            // the fields of the universe are matched by name or position
+            // and their types are inferred.
+            let mut proxy_fields = data.fields.clone();
+            for field in proxy_fields.iter_mut() {
+                if let Superset::Type(ty) = FieldData::from_attrs(&field.attrs)?.superset {
+                    return Err(Error::new(
+                        ty.span(),
+                        "`#[iffi(with = ...)]` cannot be used with `#[iffi(universe = ...)]`",
+                    ));
+                }
+                field.ty = parse_quote!(_);
+            }
+            let fields_each = fields_check(
+                proxy_fields.iter(),
+                data.fields.iter(),
+                None,
+                krate,
+                repr.packed.is_some(),
+                true,
+            )?;
+            let fields_check = fields_check(
+                proxy_fields.iter(),
+                data.fields.iter(),
+                None,
+                krate,
+                repr.packed.is_some(),
+                false,
+            )?;
+            let payload_check = type_data
+                .tag_field
+                .as_ref()
+                .map(|tag_field| {
+                    payload_check(
+                        &data.fields,
+                        tag_field,
+                        &universe.to_token_stream(),
+                        repr.packed.is_some(),
+                        krate,
+                    )
+                })
+                .transpose()?;
+            let payload_each = payload_check.as_ref().map(payload_each);
+            each_expr = Some(quote! {
+                #payload_each
+                #fields_each
+            });
+            fields_expr = Some(fields_layout(&data.fields, krate));
+            let offsets = data.fields.iter().enumerate().map(|(i, field)| {
+                let member = field.ident.as_ref().map_or_else(
+                    || Index::from(i).to_token_stream(),
+                    ToTokens::to_token_stream,
+                );
+                quote! {
+                    assert!(
+                        ::core::mem::offset_of!(#ident, #member)
+                            == ::core::mem::offset_of!(#universe, #member),
+                        "fields of the universe must have the same offsets",
+                    );
+                }
+            });
 
-    let ident = &input.ident;
+            quote! {
+                const _: () = {
+                    assert!(
+                        ::core::mem::size_of::<#ident>() == ::core::mem::size_of::<#universe>(),
+                        "the universe must have the same size",
+                    );
+                    assert!(
+                        ::core::mem::align_of::<#ident>() == ::core::mem::align_of::<#universe>(),
+                        "the universe must have the same alignment",
+                    );
+                    #( #offsets )*
+                };
 
-    let check_expr = match &input.data {
+                #payload_check
+                #fields_check
+            }
+        }
         Data::Struct(data) => {
-            let fields_def = fields_def(&data.fields)?;
-            let fields_check = fields_check(fields_def.iter(), data.fields.iter())?;
+            let fields_def = fields_def(&data.fields, krate)?;
+            let fields_each = fields_check(
+                fields_def.iter(),
+                data.fields.iter(),
+                None,
+                krate,
+                repr.packed.is_some(),
+                true,
+            )?;
+            let fields_check = fields_check(
+                fields_def.iter(),
+                data.fields.iter(),
+                None,
+                krate,
+                repr.packed.is_some(),
+                false,
+            )?;
+            trivial_check = fields_trivial(fields_def.iter(), data.fields.iter(), krate)?;
+            let payload_check = type_data
+                .tag_field
+                .as_ref()
+                .map(|tag_field| {
+                    payload_check(
+                        &data.fields,
+                        tag_field,
+                        &quote!(#krate::MaybeInvalid<Self>),
+                        repr.packed.is_some(),
+                        krate,
+                    )
+                })
+                .transpose()?;
+            for (real_field, proxy_field) in data.fields.iter().zip(fields_def.iter()) {
+                if FieldData::from_attrs(&real_field.attrs)?.payload.is_some() {
+                    if payload_check.is_none() {
+                        return Err(Error::new(
+                            real_field.span(),
+                            "`#[iffi(payload(...))]` requires `#[iffi(tag_field = ...)]` on the type",
+                        ));
+                    }
+                    continue;
+                }
+                field_bounds.push((
+                    real_field.ty.clone(),
+                    bounds::iffi_bound(&proxy_field.ty, krate),
+                ));
+            }
 
+            let mut fields_def = fields_def;
+            push_marker(&mut fields_def, &input);
             let item_data = ItemData::Struct(fields_def);
             let item = Item {
                 generics: input.generics.clone(),
@@ -261,13 +839,27 @@ fn impl_iffi(input: DeriveInput) -> Result<TokenStream, Error> {
             };
 
             let fields_ty = item.to_type_tokens();
+            let layout_assertion = layout_assertion(&fields_ty, "Fields");
+            let payload_each = payload_check.as_ref().map(payload_each);
+            fields_expr = Some(fields_layout(&data.fields, krate));
+            each_expr = Some(quote! {
+                #repr
+                #item
+
+                let superset: &#fields_ty = unsafe { ::core::mem::transmute(superset) };
+
+                #payload_each
+                #fields_each
+            });
 
             quote! {
                 #repr
                 #item
+                #layout_assertion
 
                 let superset: &#fields_ty = unsafe { ::core::mem::transmute(superset) };
 
+                #payload_check
                 #fields_check
             }
         }
@@ -276,22 +868,33 @@ fn impl_iffi(input: DeriveInput) -> Result<TokenStream, Error> {
                 Ident::new(&format!("{}Fields", ident), ident.span())
             }
 
-            let enum_repr = enum_repr.ok_or_else(|| {
-                Error::new(
-                    Span::call_site(),
-                    "enums require a primitive representation (`#[repr(u8, isize, etc.)]`)",
-                )
-            })?;
-            let discriminant_type = Type::Verbatim(enum_repr.into_token_stream());
+            let discriminant_type = match repr.int.clone() {
+                Some(enum_repr) => Type::Verbatim(enum_repr.into_token_stream()),
+                // `#[repr(C)]` enums have the same tag as the equivalent C enum.
+                None if repr.c => parse_quote!(::core::ffi::c_int),
+                None => {
+                    return Err(Error::new(
+                        Span::call_site(),
+                        "enums require a `#[repr(C)]` or primitive representation (`#[repr(u8, isize, etc.)]`)",
+                    ))
+                }
+            };
+            // `#[repr(C, Int)]` enums are laid out as a `(tag, union)` pair,
+            // whereas the variants of `#[repr(Int)]` enums each begin with their own tag.
+            let tagged = repr.c;
+            let tag_fields = usize::from(!tagged);
 
             let variant_structs = data
                 .variants
                 .iter()
                 .map(|variant| {
                     let named_fields = sanitize_fields(&variant.fields);
-                    let mut fields = fields_def(&named_fields)?;
-                    match &mut fields {
-                        Fields::Named(FieldsNamed { named, .. }) => named.insert(
+                    let mut fields = fields_def(&named_fields, krate)?;
+                    let Fields::Named(FieldsNamed { named, .. }) = &mut fields else {
+                        unreachable!();
+                    };
+                    if !tagged {
+                        named.insert(
                             0,
                             Field {
                                 attrs: Vec::new(),
@@ -301,9 +904,9 @@ fn impl_iffi(input: DeriveInput) -> Result<TokenStream, Error> {
                                 ty: discriminant_type.clone(),
                                 vis: Visibility::Inherited,
                             },
-                        ),
-                        _ => unreachable!(),
+                        );
                     }
+                    push_marker(&mut fields, &input);
 
                     Ok(Item {
                         generics: input.generics.clone(),
@@ -318,8 +921,9 @@ fn impl_iffi(input: DeriveInput) -> Result<TokenStream, Error> {
                 named: data
                     .variants
                     .iter()
-                    .map(|variant| {
-                        let variant_fields = variant_fields_ident(&variant.ident);
+                    .zip(&variant_structs)
+                    .map(|(variant, variant_struct)| {
+                        let variant_fields = variant_struct.to_type_tokens();
                         Field {
                             attrs: Vec::new(),
                             colon_token: Some(Default::default()),
@@ -335,48 +939,145 @@ fn impl_iffi(input: DeriveInput) -> Result<TokenStream, Error> {
             };
 
             let mut match_arms = Vec::new();
-            let mut base_discriminant_expr: Expr = parse_quote!(0);
-            let mut discriminant_offset = 0;
-            for (variant_struct, enum_variant) in variant_structs.iter().zip(&data.variants) {
+            let mut discriminants = Vec::new();
+            let mut discriminant_consts = Vec::new();
+            // the first and last discriminants of the current run of fieldless variants
+            // with consecutive discriminants, which are checked with a single range pattern.
+            let mut run: Option<(Ident, Ident)> = None;
+            let range_arm = |(lo, hi): (Ident, Ident)| {
+                if lo == hi {
+                    quote!(#lo => Ok(()))
+                } else {
+                    quote!(#lo..=#hi => Ok(()))
+                }
+            };
+            for (i, (variant_struct, enum_variant)) in
+                variant_structs.iter().zip(&data.variants).enumerate()
+            {
+                VariantData::from_attrs(&enum_variant.attrs)?;
                 let variant_fields = sanitize_fields(&enum_variant.fields);
 
-                if let Some((_, expr)) = &enum_variant.discriminant {
-                    base_discriminant_expr = expr.clone();
-                    discriminant_offset = 0;
+                // mirror rustc: explicit discriminants are evaluated as the tag type
+                // and implicit ones are one more than the previous variant's.
+                let discriminant = format_ident!("DISCRIMINANT_{}", i);
+                let value = match (&enum_variant.discriminant, discriminants.last()) {
+                    (Some((_, expr)), _) => quote!((#expr)),
+                    (None, Some(prev)) => quote!(#prev.wrapping_add(1)),
+                    (None, None) => quote!(0),
+                };
+                discriminant_consts.push(quote! {
+                    const #discriminant: #discriminant_type = #value;
+                });
+                discriminants.push(discriminant.clone());
+
+                if enum_variant.fields.is_empty() {
+                    run = match run.take() {
+                        Some((lo, _)) if enum_variant.discriminant.is_none() => {
+                            Some((lo, discriminant))
+                        }
+                        Some(prev) => {
+                            match_arms.push(range_arm(prev));
+                            Some((discriminant.clone(), discriminant))
+                        }
+                        None => Some((discriminant.clone(), discriminant)),
+                    };
+                    continue;
                 }
+                match_arms.extend(run.take().map(range_arm));
 
                 let ItemData::Struct(variant_struct_fields) = &variant_struct.data else {
                     unreachable!();
                 };
 
-                let fields_check =
-                    fields_check(variant_struct_fields.iter().skip(1), variant_fields.iter())?;
-                let offset = Index::from(discriminant_offset);
+                // the original fields are used so that errors name them as written.
+                let fields_check = fields_check(
+                    variant_struct_fields.iter().skip(tag_fields),
+                    enum_variant.fields.iter(),
+                    Some(&enum_variant.ident),
+                    krate,
+                    false,
+                    false,
+                )?;
+                field_bounds.extend(
+                    variant_fields
+                        .iter()
+                        .zip(variant_struct_fields.iter().skip(tag_fields))
+                        .map(|(real_field, proxy_field)| {
+                            (
+                                real_field.ty.clone(),
+                                bounds::iffi_bound(&proxy_field.ty, krate),
+                            )
+                        }),
+                );
                 let variant_ty = variant_struct.to_type_tokens();
 
                 let union_field = &enum_variant.ident;
 
                 let arm = quote! {
-                    x if x == {
-                        const EXPR: #discriminant_type = #base_discriminant_expr + #offset;
-                        EXPR
-                    } => {
+                    #discriminant => {
 
                         // SAFETY: just verified that the discriminant is correct.
-                        let superset: &#variant_ty = unsafe { &superset.#union_field };
+                        let superset: &#variant_ty = unsafe { &variants.#union_field };
 
                         #fields_check
                     }
                 };
                 match_arms.push(arm);
-                discriminant_offset += 1;
             }
+            match_arms.extend(run.take().map(range_arm));
 
             let item = Item {
                 generics: input.generics.clone(),
                 data: ItemData::Union(union_fields),
                 ident: Ident::new("Variants", data.variants.span()),
             };
+            let variants_ty = item.to_type_tokens();
+
+            let layout = if tagged {
+                let tagged_item = Item {
+                    generics: input.generics.clone(),
+                    data: ItemData::Struct(Fields::Named(parse_quote! {
+                        {
+                            tag: #discriminant_type,
+                            variants: #variants_ty,
+                        }
+                    })),
+                    ident: Ident::new("Tagged", data.variants.span()),
+                };
+                let tagged_ty = tagged_item.to_type_tokens();
+                let layout_assertion = layout_assertion(&tagged_ty, "Tagged");
+
+                quote! {
+                    #[repr(C)]
+                    #[allow(non_snake_case)]
+                    #item
+
+                    #repr
+                    #tagged_item
+                    #layout_assertion
+
+                    let superset: &#tagged_ty = unsafe { ::core::mem::transmute(superset) };
+                    let tag = superset.tag;
+                    #[allow(unused_variables)]
+                    let variants = &superset.variants;
+                }
+            } else {
+                let layout_assertion = layout_assertion(&variants_ty, "Variants");
+
+                quote! {
+                    #repr
+                    #[allow(non_snake_case)]
+                    #item
+                    #layout_assertion
+
+                    let tag = unsafe {::core::ptr::read(superset as *const _ as *const #discriminant_type) };
+                    #[allow(unused_variables)]
+                    let variants: &#variants_ty = unsafe { ::core::mem::transmute(superset) };
+                }
+            };
+
+            let variant_count = discriminants.len();
+            let duplicate_err = format!("`{ident}` has duplicate discriminants");
 
             quote! {
                 #(
@@ -384,32 +1085,238 @@ fn impl_iffi(input: DeriveInput) -> Result<TokenStream, Error> {
                     #variant_structs
                 )*
 
-                #repr
-                #[allow(non_snake_case)]
-                #item
+                #(#discriminant_consts)*
+                const DISCRIMINANTS: [#discriminant_type; #variant_count] = [#(#discriminants),*];
+                // overlapping discriminants would make the arms below ambiguous.
+                const _: () = {
+                    let mut i = 0;
+                    while i < DISCRIMINANTS.len() {
+                        let mut j = i + 1;
+                        while j < DISCRIMINANTS.len() {
+                            assert!(DISCRIMINANTS[i] != DISCRIMINANTS[j], #duplicate_err);
+                            j += 1;
+                        }
+                        i += 1;
+                    }
+                };
 
-                let tag = unsafe {::core::ptr::read(superset as *const _ as *const #discriminant_type) };
-                let superset: &Variants = unsafe { ::core::mem::transmute(superset) };
+                #layout
 
                 match tag {
                     #(
                         #match_arms,
                     )*
-                    v => Err(iffi::Error::new::<Self, iffi::MaybeInvalid<Self>>(iffi::ErrorKind::InvalidEnumDiscriminant(iffi::BitPattern::from_le(&v.to_le_bytes())))),
+                    v => Err(#krate::Error::new::<Self, #krate::MaybeInvalid<Self>>(
+                        #krate::ErrorKind::InvalidEnumDiscriminant {
+                            ty: ::core::any::type_name::<Self>(),
+                            bits: #krate::BitPattern::from_le(&v.to_le_bytes()),
+                            valid: #krate::BitRanges::from_ne_values(
+                                &DISCRIMINANTS,
+                                |discriminant| discriminant.checked_add(1),
+                            ),
+                        },
+                    )),
                 }
             }
         }
-        Data::Union(_) => return Err(Error::new(input.span(), UNION_ERR)),
+        Data::Union(data) => {
+            // unions have no validity invariant of their own,
+            // so one made only of nicheless fields is valid for any bit-pattern.
+            let field_tys = data.fields.named.iter().map(|field| &field.ty);
+            trivial_check = quote!(true);
+            field_bounds.extend(
+                field_tys
+                    .clone()
+                    .map(|ty| (ty.clone(), quote!(#krate::Nicheless))),
+            );
+
+            quote! {
+                fn nicheless<T: #krate::Nicheless>() {}
+                #( nicheless::<#field_tys>(); )*
+
+                let _ = superset;
+                Ok(())
+            }
+        }
+    };
+
+    let generics = match &type_data.bound {
+        Some(predicates) => bounds::with_predicates(&input.generics, predicates),
+        None => bounds::with_bounds(&input.generics, field_bounds),
     };
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let universe = match &type_data.universe {
+        Some(universe) => universe.to_token_stream(),
+        None => quote!(#krate::MaybeInvalid<#ident #ty_generics>),
+    };
+
+    let no_padding = type_data
+        .deny_padding
+        .then(|| no_padding(&input, krate))
+        .transpose()?;
+
+    let std_traits = type_data.impl_std_traits.then(|| {
+        quote! {
+            #[allow(clippy::multiple_bound_locations)]
+            impl #impl_generics ::core::convert::TryFrom<#universe>
+                for #ident #ty_generics #where_clause
+            {
+                type Error = #krate::Error;
+
+                fn try_from(value: #universe) -> Result<Self, #krate::Error> {
+                    #krate::try_from(value)
+                }
+            }
+
+            #[allow(clippy::multiple_bound_locations)]
+
+            impl #impl_generics ::core::convert::From<#ident #ty_generics>
+                for #universe #where_clause
+            {
+                fn from(value: #ident #ty_generics) -> Self {
+                    #krate::into(value)
+                }
+            }
+        }
+    });
+
+    let each_expr = each_expr.map(|each_expr| {
+        let each_expr = match &type_data.invariant {
+            // the invariant can only be checked once every field is known to be valid.
+            Some(_) => quote! {
+                let mut valid = true;
+                {
+                    let report = &mut |error: #krate::Error| {
+                        valid = false;
+                        report(error);
+                    };
+                    #each_expr
+                }
+                if valid {
+                    if let Err(error) = Self::can_transmute(superset) {
+                        report(error);
+                    }
+                }
+            },
+            None => each_expr,
+        };
+
+        quote! {
+            fn can_transmute_each(
+                superset: &#universe,
+                report: &mut dyn FnMut(#krate::Error),
+            ) {
+                #each_expr
+            }
+        }
+    });
+
+    let check_expr = match &type_data.invariant {
+        Some(invariant) => {
+            trivial_check = quote!(false);
+            quote! {
+                let check_fields = || -> Result<(), #krate::Error> {
+                    #check_expr
+                };
+                check_fields()?;
 
-    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+                // SAFETY: every field was just validated.
+                let value = unsafe { &*(superset as *const #universe as *const Self) };
+                #invariant(value).map_err(#krate::Error::new::<Self, #universe>)
+            }
+        }
+        None => check_expr,
+    };
 
     Ok(quote! {
+        #std_traits
+        #no_padding
+
         // SAFETY: universe has same layout and check_expr is valid.
-        unsafe impl #impl_generics iffi::Iffi for #ident #ty_generics #where_clause {
-            fn can_transmute(superset: &iffi::MaybeInvalid<Self>) -> Result<(), iffi::Error> {
+        #[allow(clippy::multiple_bound_locations)]
+        unsafe impl #impl_generics #krate::Iffi<#universe> for #ident #ty_generics #where_clause {
+            const TRIVIAL_CHECK: bool = #trivial_check;
+
+            #fields_expr
+
+            fn can_transmute(superset: &#universe) -> Result<(), #krate::Error> {
                 #check_expr
             }
+
+            #each_expr
         }
     })
 }
+
+/// Implements `NoPadding` for a struct whose fields fill it exactly.
+fn no_padding(input: &DeriveInput, krate: &Path) -> Result<TokenStream, Error> {
+    let ident = &input.ident;
+    if !input.generics.params.is_empty() {
+        return Err(Error::new(
+            input.generics.span(),
+            "`#[iffi(deny_padding)]` does not support generic types",
+        ));
+    }
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new(
+            ident.span(),
+            "`#[iffi(deny_padding)]` only supports structs",
+        ));
+    };
+
+    let field_tys: Vec<_> = data.fields.iter().map(|field| &field.ty).collect();
+    let padding_err = format!("`{ident}` has padding between or after its fields");
+    Ok(quote! {
+        const _: () = {
+            fn fields_have_no_padding() {
+                fn no_padding<T: #krate::NoPadding>() {}
+
+                #( no_padding::<#field_tys>(); )*
+            }
+
+            assert!(
+                0 #( + ::core::mem::size_of::<#field_tys>() )* == ::core::mem::size_of::<#ident>(),
+                #padding_err,
+            );
+        };
+
+        // SAFETY: no field has padding and the fields fill the struct.
+        unsafe impl #krate::NoPadding for #ident {}
+    })
+}
+
+/// Lists the name, offset and size of each field for `Iffi::FIELDS`.
+fn fields_layout(fields: &Fields, krate: &Path) -> TokenStream {
+    let layouts = fields.iter().enumerate().map(|(i, field)| {
+        let member = field.ident.as_ref().map_or_else(
+            || Index::from(i).to_token_stream(),
+            ToTokens::to_token_stream,
+        );
+        let name = member.to_string();
+        let ty = &field.ty;
+        quote! {
+            #krate::FieldLayout {
+                name: #name,
+                offset: ::core::mem::offset_of!(Self, #member),
+                size: ::core::mem::size_of::<#ty>(),
+            }
+        }
+    });
+
+    quote! {
+        const FIELDS: &'static [#krate::FieldLayout] = &[#(#layouts),*];
+    }
+}
+
+/// Reports the error of a `payload_check`, if any, for `Iffi::can_transmute_each`.
+fn payload_each(payload_check: &TokenStream) -> TokenStream {
+    quote! {
+        let payload = || -> Result<(), _> {
+            #payload_check
+            Ok(())
+        };
+        if let Err(error) = payload() {
+            report(error);
+        }
+    }
+}
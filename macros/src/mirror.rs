@@ -0,0 +1,206 @@
+use proc_macro2::{Ident, TokenStream, TokenTree};
+use quote::{quote, ToTokens};
+use syn::{
+    parse::{Parse, ParseStream},
+    spanned::Spanned,
+    Attribute, Data, DeriveInput, Error, Fields, Visibility,
+};
+
+use crate::{
+    attrs::TypeData, fields_check, fields_def, fields_trivial, no_padding, payload_check,
+    repr::Repr,
+};
+
+/// The arguments of `#[iffi::mirror(pub RawFoo)]`.
+pub struct MirrorArgs {
+    vis: Visibility,
+    ident: Ident,
+}
+
+impl Parse for MirrorArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        Ok(Self {
+            vis: input.parse()?,
+            ident: input.parse()?,
+        })
+    }
+}
+
+/// Whether the attributes derive `Iffi`, which needs the `#[iffi]` attributes to be kept.
+fn derives_iffi(attrs: &[Attribute]) -> Result<bool, Error> {
+    let mut derives = false;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("derive")) {
+        attr.parse_nested_meta(|meta| {
+            derives |= meta
+                .path
+                .segments
+                .last()
+                .is_some_and(|segment| segment.ident == "Iffi");
+
+            Ok(())
+        })?;
+    }
+
+    Ok(derives)
+}
+
+/// Rejects a type attribute parameter that the mirror cannot honour.
+fn unsupported(attrs: &[Attribute], param: &str, reason: &str) -> Error {
+    // point at the parameter in the `#[iffi(...)]` attribute that names it.
+    let span = attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("iffi"))
+        .filter_map(|attr| attr.meta.require_list().ok())
+        .flat_map(|list| list.tokens.clone())
+        .find_map(|token| match token {
+            TokenTree::Ident(ident) if ident == param => Some(ident.span()),
+            _ => None,
+        });
+    Error::new(
+        span.unwrap_or_else(proc_macro2::Span::call_site),
+        format!("`#[iffi::mirror]` does not support `#[iffi({param})]`: {reason}"),
+    )
+}
+
+pub fn impl_mirror(args: MirrorArgs, mut input: DeriveInput) -> Result<TokenStream, Error> {
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new(
+            input.span(),
+            "`#[iffi::mirror]` only supports structs",
+        ));
+    };
+    if !input.generics.params.is_empty() {
+        return Err(Error::new(
+            input.generics.span(),
+            "`#[iffi::mirror]` does not support generic types",
+        ));
+    }
+
+    let type_data = TypeData::from_attrs(&input.attrs)?;
+    let krate = &type_data.krate;
+    let derives_iffi = derives_iffi(&input.attrs)?;
+    if type_data.bound.is_some() {
+        return Err(unsupported(
+            &input.attrs,
+            "bound",
+            "the mirrored type cannot be generic",
+        ));
+    }
+    if type_data.universe.is_some() {
+        return Err(unsupported(
+            &input.attrs,
+            "universe",
+            "the mirror is the universe",
+        ));
+    }
+    if type_data.impl_std_traits && !derives_iffi {
+        return Err(unsupported(
+            &input.attrs,
+            "impl_std_traits",
+            "the conversions to and from the mirror are always implemented, \
+             and those for `MaybeInvalid<Self>` need `#[derive(Iffi)]`",
+        ));
+    }
+    let krate_str = krate.to_token_stream().to_string();
+    let packed = Repr::from_attrs(&input.attrs)?.is_some_and(|repr| repr.packed.is_some());
+
+    let ident = &input.ident;
+    let MirrorArgs {
+        vis,
+        ident: raw_ident,
+    } = args;
+    let raw_fields = fields_def(&data.fields, krate)?;
+    let repr = input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("repr"));
+    let semi = (!matches!(raw_fields, Fields::Named(_))).then(|| quote!(;));
+
+    let payload_check = type_data
+        .tag_field
+        .as_ref()
+        .map(|tag_field| payload_check(&data.fields, tag_field, &quote!(#raw_ident), packed, krate))
+        .transpose()?;
+    let fields_check = fields_check(
+        raw_fields.iter(),
+        data.fields.iter(),
+        None,
+        krate,
+        packed,
+        false,
+    )?;
+    let mut trivial_check = fields_trivial(raw_fields.iter(), data.fields.iter(), krate)?;
+    let check_expr = match &type_data.invariant {
+        Some(invariant) => {
+            trivial_check = quote!(false);
+            quote! {
+                let check_fields = || -> Result<(), #krate::Error> {
+                    #payload_check
+                    #fields_check
+                };
+                check_fields()?;
+
+                // SAFETY: every field was just validated.
+                let value = unsafe { &*(superset as *const #raw_ident as *const Self) };
+                #invariant(value).map_err(#krate::Error::new::<Self, #raw_ident>)
+            }
+        }
+        None => quote! {
+            #payload_check
+            #fields_check
+        },
+    };
+    // a derive of `Iffi` implements `NoPadding` itself.
+    let no_padding = (type_data.deny_padding && !derives_iffi)
+        .then(|| no_padding(&input, krate))
+        .transpose()?;
+
+    let raw = quote! {
+        #(#repr)*
+        #[derive(#krate::Nicheless)]
+        #[iffi(crate = #krate_str)]
+        #vis struct #raw_ident #raw_fields #semi
+    };
+
+    // the `#[iffi]` attributes are only understood by the derives.
+    if !derives_iffi {
+        let is_iffi = |attr: &Attribute| attr.path().is_ident("iffi");
+        input.attrs.retain(|attr| !is_iffi(attr));
+        if let Data::Struct(data) = &mut input.data {
+            data.fields
+                .iter_mut()
+                .for_each(|field| field.attrs.retain(|attr| !is_iffi(attr)));
+        }
+    }
+
+    Ok(quote! {
+        #input
+
+        #raw
+
+        #no_padding
+
+        // SAFETY: the mirror has the same representation and fields with the same layouts.
+        unsafe impl #krate::Iffi<#raw_ident> for #ident {
+            const TRIVIAL_CHECK: bool = #trivial_check;
+
+            fn can_transmute(superset: &#raw_ident) -> Result<(), #krate::Error> {
+                #check_expr
+            }
+        }
+
+        impl ::core::convert::TryFrom<#raw_ident> for #ident {
+            type Error = #krate::Error;
+
+            fn try_from(value: #raw_ident) -> Result<Self, #krate::Error> {
+                #krate::try_from(value)
+            }
+        }
+
+        impl ::core::convert::From<#ident> for #raw_ident {
+            fn from(value: #ident) -> Self {
+                #krate::into(value)
+            }
+        }
+    })
+}
@@ -1,11 +1,11 @@
 use proc_macro2::{Ident, TokenStream};
 use quote::{quote, ToTokens};
 use syn::{
-    punctuated::Punctuated, spanned::Spanned, Data, DeriveInput, Error, Fields, FieldsNamed,
-    Generics, Member, Token, Variant,
+    punctuated::Punctuated, Attribute, Data, DeriveInput, Error, Fields, FieldsNamed, Generics,
+    Member, Path, Token, Type, Variant,
 };
 
-use crate::UNION_ERR;
+use crate::{bounds, repr::Repr};
 
 pub struct Item {
     pub generics: Generics,
@@ -93,16 +93,44 @@ fn fields_are_nicheless(fields: &Fields) -> TokenStream {
     }
 }
 
-pub fn impl_nicheless(input: &Item) -> Result<TokenStream, Error> {
+pub fn impl_nicheless(
+    input: &Item,
+    attrs: &[Attribute],
+    krate: &Path,
+) -> Result<TokenStream, Error> {
     let ident = &input.ident;
+    // the default representation has no stable layout to share with foreign code.
+    let repr = Repr::from_attrs(attrs)?.ok_or_else(|| {
+        Error::new(
+            ident.span(),
+            "nicheless types must be FFI-safe; \
+            expected `#[repr(C)]`, `#[repr(transparent)]` or a primitive representation",
+        )
+    })?;
+    let field_tys: Vec<Type> = match &input.data {
+        ItemData::Struct(fields) => fields.iter().map(|field| field.ty.clone()).collect(),
+        ItemData::Enum(variants) => variants
+            .iter()
+            .flat_map(|variant| variant.fields.iter().map(|field| field.ty.clone()))
+            .collect(),
+        ItemData::Union(fields) => fields.named.iter().map(|field| field.ty.clone()).collect(),
+    };
+    let generics = bounds::with_bounds(
+        &input.generics,
+        field_tys
+            .into_iter()
+            .map(|ty| (ty, quote!(#krate::Nicheless))),
+    );
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
     let assertion = match &input.data {
         ItemData::Struct(fields) => {
             let fields = fields_are_nicheless(fields);
             quote! {
                 const _: () = {
-                    fn all_fields_are_nicheless(value: #ident) {
-                        fn nicheless(n: impl iffi::Nicheless) {}
+                    #[allow(clippy::multiple_bound_locations)]
+                    fn all_fields_are_nicheless #impl_generics (value: #ident #ty_generics) #where_clause {
+                        fn nicheless(n: impl #krate::Nicheless) {}
 
                         #fields
                     }
@@ -110,34 +138,70 @@ pub fn impl_nicheless(input: &Item) -> Result<TokenStream, Error> {
             }
         }
         ItemData::Enum(variants) => {
-            let variants = variants.iter().map(|variant| {
-                let fields = fields_are_nicheless(&variant.fields);
-                let variant = &variant.ident;
-                quote! {
-                    #ident::#variant => {
-                        #fields
-                    },
+            // an enum is only nicheless if every value of its tag is a discriminant.
+            let bits = match repr.int.as_ref().map(ToString::to_string).as_deref() {
+                Some("u8" | "i8") => 8,
+                Some("u16" | "i16") => 16,
+                Some(_) => {
+                    return Err(Error::new(
+                        ident.span(),
+                        "enums with tags wider than 16 bits cannot have a variant for every tag",
+                    ))
+                }
+                None => {
+                    return Err(Error::new(
+                        ident.span(),
+                        "nicheless enums require a primitive representation (`#[repr(u8)]` or `#[repr(u16)]`)",
+                    ))
                 }
-            });
+            };
+            if variants.len() != 1 << bits {
+                return Err(Error::new(
+                    ident.span(),
+                    format_args!(
+                        "nicheless enums must have a variant for every tag value, \
+                        but this enum has {} of {}",
+                        variants.len(),
+                        1 << bits,
+                    ),
+                ));
+            }
+
+            let field_tys = variants
+                .iter()
+                .flat_map(|variant| variant.fields.iter().map(|field| &field.ty));
+            quote! {
+                const _: () = {
+                    #[allow(clippy::multiple_bound_locations)]
+                    fn all_fields_are_nicheless #impl_generics () #where_clause {
+                        fn nicheless<T: #krate::Nicheless>() {}
 
+                        #( nicheless::<#field_tys>(); )*
+                    }
+                };
+            }
+        }
+        ItemData::Union(fields) => {
+            // union fields cannot be moved out of, so assert on their types instead.
+            let field_tys = fields.named.iter().map(|field| &field.ty);
             quote! {
                 const _: () = {
-                    fn all_fields_are_nicheless(value: #ident) {
-                        fn nicheless(n: impl iffi::Nicheless) {}
+                    #[allow(clippy::multiple_bound_locations)]
+                    fn all_fields_are_nicheless #impl_generics () #where_clause {
+                        fn nicheless<T: #krate::Nicheless>() {}
 
-                        match {
-                            #(#variants)*
-                        }
+                        #( nicheless::<#field_tys>(); )*
                     }
                 };
             }
         }
-        ItemData::Union(_) => return Err(Error::new(input.span(), UNION_ERR)),
     };
 
     Ok(quote! {
         #assertion
 
-        unsafe impl iffi::Nicheless for #ident {}
+        #[allow(clippy::multiple_bound_locations)]
+
+        unsafe impl #impl_generics #krate::Nicheless for #ident #ty_generics #where_clause {}
     })
 }
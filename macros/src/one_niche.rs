@@ -0,0 +1,107 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{spanned::Spanned, Data, DeriveInput, Error, Expr, Path};
+
+use crate::{
+    bounds,
+    repr::{LayoutRepr, Repr},
+};
+
+/// The value of `#[one_niche(value = ...)]`.
+fn niche_value(input: &DeriveInput) -> Result<Expr, Error> {
+    let mut value = None;
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("one_niche"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("value") {
+                value = Some(meta.value()?.parse()?);
+
+                Ok(())
+            } else {
+                Err(meta.error("expected `value = ...`"))
+            }
+        })?;
+    }
+
+    value.ok_or_else(|| {
+        Error::new(
+            input.ident.span(),
+            "`OneNiche` requires the niche to be declared with `#[one_niche(value = ...)]`",
+        )
+    })
+}
+
+pub fn impl_one_niche(input: &DeriveInput, krate: &Path) -> Result<TokenStream, Error> {
+    let value = niche_value(input)?;
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new(
+            input.span(),
+            "`OneNiche` can only be derived for newtype structs",
+        ));
+    };
+    let mut fields = data.fields.iter();
+    let (Some(inner), None) = (fields.next(), fields.next()) else {
+        return Err(Error::new(
+            data.fields.span(),
+            "`OneNiche` can only be derived for structs with exactly one field",
+        ));
+    };
+    if !matches!(
+        Repr::from_attrs(&input.attrs)?,
+        Some(Repr {
+            layout: LayoutRepr::Transparent,
+            ..
+        })
+    ) {
+        return Err(Error::new(
+            input.ident.span(),
+            "`OneNiche` can only be derived for `#[repr(transparent)]` structs",
+        ));
+    }
+
+    let ident = &input.ident;
+    let inner_ty = &inner.ty;
+    let generics = bounds::with_predicates(
+        &input.generics,
+        &[syn::parse_quote!(#inner_ty: #krate::OneNiche)],
+    );
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    // the niche of a generic type cannot be inspected in a constant.
+    let value_check = input.generics.params.is_empty().then(|| {
+        quote! {
+            const _: () = {
+                const SIZE: usize = ::core::mem::size_of::<#ident>();
+                const NONE: ::core::option::Option<#ident> = ::core::option::Option::None;
+                // SAFETY: fails to compile unless `None` fills the niche
+                // and so has the same size as the type.
+                let none: [u8; SIZE] = unsafe { ::core::mem::transmute(NONE) };
+                let niche = (#value as u128).to_le_bytes();
+                let mut i = 0;
+                while i < SIZE {
+                    let byte = if cfg!(target_endian = "little") {
+                        none[i]
+                    } else {
+                        none[SIZE - 1 - i]
+                    };
+                    assert!(
+                        byte == niche[i],
+                        "the declared niche does not match the niche of the inner type",
+                    );
+                    i += 1;
+                }
+            };
+        }
+    });
+
+    Ok(quote! {
+        #value_check
+
+        // SAFETY: transparent over a type with exactly one niche.
+        #[allow(clippy::multiple_bound_locations)]
+        unsafe impl #impl_generics #krate::OneNiche for #ident #ty_generics #where_clause {}
+    })
+}
@@ -0,0 +1,111 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::{quote, ToTokens};
+use syn::{parenthesized, spanned::Spanned, token, Attribute, Error, Index, LitInt};
+
+pub const MISSING_REPR_ERR: &str = "Expected type to be `#[repr(C)]` or `#[repr(transparent)]`";
+
+/// The FFI-relevant parts of a type's `#[repr(...)]` attributes.
+pub struct Repr {
+    pub layout: LayoutRepr,
+    /// Whether `C` was given explicitly, rather than implied by a primitive representation.
+    pub c: bool,
+    /// The primitive integer in `#[repr(Int)]` or `#[repr(C, Int)]`.
+    pub int: Option<Ident>,
+    pub align: Option<usize>,
+    /// `Some(None)` for `packed` and `Some(Some(n))` for `packed(n)`.
+    pub packed: Option<Option<usize>>,
+}
+
+pub enum LayoutRepr {
+    C,
+    Transparent,
+}
+
+impl Repr {
+    /// Parses the `#[repr(...)]` attributes,
+    /// returning `None` if they do not give the type an FFI-safe layout.
+    pub fn from_attrs(attrs: &[Attribute]) -> Result<Option<Self>, Error> {
+        let mut layout = None;
+        let mut c = false;
+        let mut int = None;
+        let mut align = None;
+        let mut packed = None;
+
+        attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("repr"))
+            .try_for_each(|attr| {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("C") {
+                        layout = Some(LayoutRepr::C);
+                        c = true;
+                    } else if meta.path.is_ident("transparent") {
+                        layout = Some(LayoutRepr::Transparent);
+                    } else if meta.path.is_ident("align") {
+                        let content;
+                        parenthesized!(content in meta.input);
+                        let lit: LitInt = content.parse()?;
+                        align = Some(lit.base10_parse()?);
+                    } else if meta.path.is_ident("packed") {
+                        packed = Some(None);
+                        if meta.input.peek(token::Paren) {
+                            let content;
+                            parenthesized!(content in meta.input);
+                            let lit: LitInt = content.parse()?;
+                            packed = Some(Some(lit.base10_parse()?));
+                        }
+                    } else if let Some(ident) = meta.path.get_ident().map(ToString::to_string) {
+                        match ident.as_str() {
+                            "u8" | "u16" | "u32" | "u64" | "u128" | "usize" | "i8" | "i16"
+                            | "i32" | "i64" | "i128" | "isize" => {
+                                let ident = Ident::new(&ident, meta.path.span());
+                                layout = Some(LayoutRepr::C);
+                                int = Some(ident);
+                            }
+                            _ => (),
+                        }
+                    }
+
+                    Ok(())
+                })
+            })?;
+
+        Ok(layout.map(|layout| Self {
+            layout,
+            c,
+            int,
+            align,
+            packed,
+        }))
+    }
+}
+
+impl ToTokens for Repr {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let layout = match &self.layout {
+            LayoutRepr::C => quote!(C),
+            LayoutRepr::Transparent => quote!(transparent),
+        };
+        let align = self.align.map(|n| {
+            let align = Index::from(n).into_token_stream();
+            quote!(align(#align))
+        });
+        let packed = self.packed.map(|n| match n {
+            Some(n) => {
+                let packed = Index::from(n).into_token_stream();
+                quote!(packed(#packed))
+            }
+            None => quote!(packed),
+        });
+        let mut list = Vec::new();
+        list.push(layout);
+        list.extend(align);
+        list.extend(packed);
+
+        tokens.extend(quote! {
+            #[repr(
+                #(#list),*
+            )]
+        })
+    }
+}
@@ -0,0 +1,58 @@
+use core::any::type_name;
+
+use abi_stable::std_types::{ROption, RResult};
+
+use crate::{BitPattern, BitRanges, Error, ErrorKind, Iffi, MaybeInvalid};
+
+/// The layout of a variant of a `#[repr(u8)]` enum with one field.
+#[repr(C)]
+struct Variant<T> {
+    _tag: u8,
+    value: MaybeInvalid<T>,
+}
+
+/// Reads the tag of a `#[repr(u8)]` enum, rejecting any greater than `max`.
+fn tag<E: Iffi>(superset: &MaybeInvalid<E>, max: u8) -> Result<u8, Error> {
+    // SAFETY: `#[repr(u8)]` enums start with their `u8` tag.
+    let tag = unsafe { *(superset.as_ptr() as *const u8) };
+    if tag <= max {
+        return Ok(tag);
+    }
+    Err(Error::new::<E, MaybeInvalid<E>>(
+        ErrorKind::InvalidEnumDiscriminant {
+            ty: type_name::<E>(),
+            bits: BitPattern::from_ne(&tag),
+            valid: BitRanges::from_ne(&[0u8..=max]),
+        },
+    ))
+}
+
+/// Checks the field of the variant of a `#[repr(u8)]` enum.
+fn variant<T: Iffi, E: Iffi>(superset: &MaybeInvalid<E>, name: &'static str) -> Result<(), Error> {
+    // SAFETY: every variant of a `#[repr(u8)]` enum is laid out as a `#[repr(C)]` struct
+    // of the tag followed by its fields, which is no larger than the enum.
+    let variant = unsafe { &*(superset.as_ptr() as *const Variant<T>) };
+    T::can_transmute(&variant.value).map_err(|error| error.in_field::<E>(name))
+}
+
+// SAFETY: `ROption` is `#[repr(u8)]` with `RSome` as 0 and `RNone` as 1,
+// and the field of `RSome` is checked.
+unsafe impl<T: Iffi> Iffi<MaybeInvalid<Self>> for ROption<T> {
+    fn can_transmute(superset: &MaybeInvalid<Self>) -> Result<(), Error> {
+        match tag(superset, 1)? {
+            0 => variant::<T, Self>(superset, "RSome"),
+            _ => Ok(()),
+        }
+    }
+}
+
+// SAFETY: `RResult` is `#[repr(u8)]` with `ROk` as 0 and `RErr` as 1,
+// and the field of each is checked.
+unsafe impl<T: Iffi, E: Iffi> Iffi<MaybeInvalid<Self>> for RResult<T, E> {
+    fn can_transmute(superset: &MaybeInvalid<Self>) -> Result<(), Error> {
+        match tag(superset, 1)? {
+            0 => variant::<T, Self>(superset, "ROk"),
+            _ => variant::<E, Self>(superset, "RErr"),
+        }
+    }
+}
@@ -1,6 +1,6 @@
-use core::{fmt, ops::RangeInclusive};
+use core::{cmp::Ordering, fmt, ops::RangeInclusive};
 
-use alloc::{boxed::Box, format};
+use alloc::{boxed::Box, vec::Vec};
 use bytemuck::Pod;
 use smallvec::{SmallVec, ToSmallVec};
 
@@ -9,52 +9,138 @@ use smallvec::{SmallVec, ToSmallVec};
 /// See [the glossary entry] for more detail.
 ///
 /// [the glossary entry]: crate#bit-pattern
-#[derive(PartialEq, Eq, Debug, Clone, PartialOrd, Ord)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone, PartialOrd, Ord)]
 pub struct BitPattern(SmallVec<[u8; 20]>);
 
 impl BitPattern {
+    /// Creates a pattern from a value in native order, such as an integer,
+    /// stored as little-endian like every pattern.
+    ///
+    /// The same as [`from_ne`]: the bytes of the value are reversed on big-endian targets,
+    /// so bytes that are already in little-endian order should use [`from_le_bytes`].
+    /// Earlier versions had no other constructor, so the name is kept for compatibility.
+    ///
+    /// [`from_ne`]: BitPattern::from_ne
+    /// [`from_le_bytes`]: BitPattern::from_le_bytes
     pub fn from_le<T: Pod>(value: &T) -> Self {
-        #[allow(unused_mut)]
-        let mut bytes = bytemuck::bytes_of(value).to_smallvec();
-        #[cfg(target_endian = "big")]
-        bytes.reverse();
-        Self(bytes)
+        Self::from_ne(value)
     }
-}
 
-impl fmt::Display for BitPattern {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "0x")?;
-        for byte in self.0.iter().rev() {
-            write!(f, "{byte:02x}")?;
+    /// Creates a pattern from a value whose bytes are in big-endian order.
+    pub fn from_be<T: Pod>(value: &T) -> Self {
+        Self(bytemuck::bytes_of(value).iter().rev().copied().collect())
+    }
+
+    /// Creates a pattern from a value whose bytes are in native order, such as an integer.
+    pub fn from_ne<T: Pod>(value: &T) -> Self {
+        if cfg!(target_endian = "big") {
+            Self::from_be(value)
+        } else {
+            Self::from_le_bytes(bytemuck::bytes_of(value))
         }
+    }
+
+    /// Creates a pattern from its bytes in little-endian order.
+    pub fn from_le_bytes(bytes: &[u8]) -> Self {
+        Self(bytes.to_smallvec())
+    }
 
-        Ok(())
+    /// The bytes of the pattern in little-endian order.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
     }
 }
 
 /// A set of ranges of bit-patterns that are applicable for a type.
-#[derive(PartialEq, Eq, Debug, Clone)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone)]
 pub struct BitRanges(Box<SmallVec<[RangeInclusive<BitPattern>; 1]>>);
 
+/// Ranges are ordered lexicographically by their starts and then their ends.
+impl Ord for BitRanges {
+    fn cmp(&self, other: &Self) -> Ordering {
+        fn bounds(range: &RangeInclusive<BitPattern>) -> (&BitPattern, &BitPattern) {
+            (range.start(), range.end())
+        }
+        self.0.iter().map(bounds).cmp(other.0.iter().map(bounds))
+    }
+}
+
+impl PartialOrd for BitRanges {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl fmt::Display for BitRanges {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_list()
-            .entries(
-                self.0
-                    .iter()
-                    .map(|pat| format!("{}..={}", pat.start(), pat.end())),
-            )
-            .finish()
+        let mut list = f.debug_list();
+        for range in self.0.iter() {
+            list.entry(&format_args!("{}..={}", range.start(), range.end()));
+        }
+        list.finish()
     }
 }
 
 impl BitRanges {
-    pub fn from_le<T: Pod>(value: &[RangeInclusive<T>]) -> Self {
-        let boxed =
-            SmallVec::from_iter(value.iter().map(|range| {
-                BitPattern::from_le(range.start())..=BitPattern::from_le(range.end())
-            }));
+    fn from_with<T: Pod>(value: &[RangeInclusive<T>], pattern: fn(&T) -> BitPattern) -> Self {
+        let boxed = SmallVec::from_iter(
+            value
+                .iter()
+                .map(|range| pattern(range.start())..=pattern(range.end())),
+        );
         Self(Box::new(boxed))
     }
+
+    /// Creates ranges from values in native order, such as integers.
+    ///
+    /// The same as [`from_ne`], as with [`BitPattern::from_le`].
+    ///
+    /// [`from_ne`]: BitRanges::from_ne
+    pub fn from_le<T: Pod>(value: &[RangeInclusive<T>]) -> Self {
+        Self::from_with(value, BitPattern::from_le)
+    }
+
+    /// Creates ranges from values whose bytes are in big-endian order.
+    pub fn from_be<T: Pod>(value: &[RangeInclusive<T>]) -> Self {
+        Self::from_with(value, BitPattern::from_be)
+    }
+
+    /// Creates ranges from values whose bytes are in native order, such as integers.
+    pub fn from_ne<T: Pod>(value: &[RangeInclusive<T>]) -> Self {
+        Self::from_with(value, BitPattern::from_ne)
+    }
+
+    /// Collects a set of values, such as the discriminants of an enum,
+    /// into the fewest ranges that contain them.
+    ///
+    /// `succ` returns the value after another, if there is one.
+    pub fn from_ne_values<T: Pod + Ord>(values: &[T], succ: impl Fn(T) -> Option<T>) -> Self {
+        let mut sorted = values.to_vec();
+        sorted.sort_unstable();
+        sorted.dedup();
+
+        let mut ranges: Vec<RangeInclusive<T>> = Vec::new();
+        for value in sorted {
+            match ranges.last_mut() {
+                Some(range) if succ(*range.end()) == Some(value) => {
+                    *range = *range.start()..=value;
+                }
+                _ => ranges.push(value..=value),
+            }
+        }
+        Self::from_ne(&ranges)
+    }
+
+    /// The ranges of bit-patterns.
+    #[cfg(any(feature = "serde", feature = "defmt"))]
+    pub(crate) fn ranges(&self) -> impl Iterator<Item = RangeInclusive<BitPattern>> + '_ {
+        self.0.iter().cloned()
+    }
+
+    #[cfg(feature = "serde")]
+    pub(crate) fn from_ranges(
+        ranges: impl IntoIterator<Item = RangeInclusive<BitPattern>>,
+    ) -> Self {
+        Self(Box::new(ranges.into_iter().collect()))
+    }
 }
@@ -0,0 +1,202 @@
+use core::mem::ManuallyDrop;
+
+#[cfg(target_has_atomic = "ptr")]
+use alloc::sync::Arc;
+use alloc::{boxed::Box, rc::Rc, vec::Vec};
+
+use crate::{Error, Iffi, Nicheless};
+
+/// Checks whether an FFI-safe [nicheless] value can be converted to an ergonomic one,
+/// collecting every error rather than stopping at the first.
+///
+/// [nicheless]: Nicheless
+pub fn can_transmute_all<T: Iffi<U>, U: Nicheless>(value: &U) -> Result<(), Vec<Error>> {
+    let mut errors = Vec::new();
+    T::can_transmute_each(value, &mut |error| errors.push(error));
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Tries to convert an FFI-safe [nicheless] type to a more ergonomic one,
+/// collecting every error rather than stopping at the first.
+///
+/// Useful for diagnosing a corrupt value with more than one invalid field.
+///
+/// [nicheless]: Nicheless
+pub fn try_from_all<T: Iffi<U>, U: Nicheless>(value: U) -> Result<T, Vec<Error>> {
+    can_transmute_all::<T, U>(&value)?;
+    crate::assert_same_size::<T, U>();
+    // SAFETY: the superset and the subset are the same size
+    // and no errors were reported, so the value is safe to transmute.
+    unsafe { Ok(transmute::transmute(value)) }
+}
+
+/// Tries to convert a boxed FFI-safe [nicheless] type to a boxed ergonomic one.
+///
+/// Unlike [`try_from`], the value is validated behind the pointer
+/// and never moved out of its allocation.
+///
+/// [nicheless]: Nicheless
+/// [`try_from`]: crate::try_from
+pub fn try_from_boxed<T: Iffi<U>, U: Nicheless>(value: Box<U>) -> Result<Box<T>, Error> {
+    T::can_transmute(&value)?;
+    crate::assert_same_size::<T, U>();
+    // SAFETY: the Iffi trait guarantees that T and U have the same layout
+    // and the value behind the pointer is safe to transmute.
+    unsafe { Ok(Box::from_raw(Box::into_raw(value) as *mut T)) }
+}
+
+/// Takes ownership of a nullable raw pointer from foreign code as an optional [`Box`],
+/// checking that it is aligned.
+///
+/// A null pointer is `None`, and a misaligned one is an [`ErrorKind::Misaligned`] error.
+///
+/// # Safety
+///
+/// Only null and alignment are checked.
+/// If `ptr` is non-null, it must satisfy the requirements of [`Box::from_raw`].
+///
+/// [`ErrorKind::Misaligned`]: crate::ErrorKind::Misaligned
+pub unsafe fn try_option_box_from_ptr<T>(ptr: *mut T) -> Result<Option<Box<T>>, Error> {
+    crate::check_aligned(ptr).map_err(|error| Error {
+        error,
+        from: core::any::type_name::<*mut T>(),
+        into: core::any::type_name::<Option<Box<T>>>(),
+        path: crate::FieldPath::default(),
+    })?;
+    if ptr.is_null() {
+        return Ok(None);
+    }
+    // SAFETY: the pointer is non-null, aligned and, by the caller, owns a valid boxed `T`.
+    unsafe { Ok(Some(Box::from_raw(ptr))) }
+}
+
+/// Tries to convert a borrowed FFI-safe [nicheless] value to a boxed ergonomic one.
+///
+/// The value is validated in place and then copied straight into a new allocation,
+/// so it is never copied onto the stack.
+///
+/// [nicheless]: Nicheless
+pub fn try_from_ref_boxed<T: Iffi<U> + Copy, U: Nicheless>(value: &U) -> Result<Box<T>, Error> {
+    T::can_transmute(value)?;
+    let mut boxed = Box::<T>::new_uninit();
+    // SAFETY: T and U have the same layout and the value is safe to transmute.
+    // T is `Copy` so copying it out of the borrowed value cannot duplicate ownership.
+    unsafe {
+        core::ptr::copy_nonoverlapping(value as *const U as *const T, boxed.as_mut_ptr(), 1);
+        Ok(boxed.assume_init())
+    }
+}
+
+/// Converts a boxed ergonomic Rust type to a boxed FFI-safe [nicheless] type.
+///
+/// Unlike [`into`], the value is never moved out of its allocation.
+///
+/// [nicheless]: Nicheless
+/// [`into`]: crate::into
+pub fn into_boxed<T: Iffi<U>, U: Nicheless>(safe: Box<T>) -> Box<U> {
+    crate::assert_same_size::<T, U>();
+    // SAFETY: the Iffi trait guarantees that U is a superset of T with the same layout.
+    unsafe { Box::from_raw(Box::into_raw(safe) as *mut U) }
+}
+
+/// Tries to convert a boxed slice of FFI-safe [nicheless] values to a boxed slice of ergonomic ones.
+///
+/// Every element is validated in place and the allocation is reused.
+///
+/// [nicheless]: Nicheless
+pub fn try_from_boxed_slice<T: Iffi<U>, U: Nicheless>(values: Box<[U]>) -> Result<Box<[T]>, Error> {
+    crate::try_from_slice::<T, U>(&values)?;
+    crate::assert_same_size::<T, U>();
+    // SAFETY: T and U have the same layout and every element is safe to transmute.
+    unsafe { Ok(Box::from_raw(Box::into_raw(values) as *mut [T])) }
+}
+
+/// Converts a boxed slice of ergonomic Rust values to a boxed slice of FFI-safe [nicheless] ones.
+///
+/// [nicheless]: Nicheless
+pub fn into_boxed_slice<T: Iffi<U>, U: Nicheless>(safe: Box<[T]>) -> Box<[U]> {
+    crate::assert_same_size::<T, U>();
+    // SAFETY: the Iffi trait guarantees that U is a superset of T with the same layout.
+    unsafe { Box::from_raw(Box::into_raw(safe) as *mut [U]) }
+}
+
+/// Tries to convert a vector of FFI-safe [nicheless] values to a vector of ergonomic ones.
+///
+/// Every element is validated in place and the allocation is reused.
+///
+/// [nicheless]: Nicheless
+pub fn try_from_vec<T: Iffi<U>, U: Nicheless>(values: Vec<U>) -> Result<Vec<T>, Error> {
+    crate::try_from_slice::<T, U>(&values)?;
+    crate::assert_same_size::<T, U>();
+    let mut values = ManuallyDrop::new(values);
+    // SAFETY: T and U have the same layout and every element is safe to transmute.
+    // the original vector is never dropped.
+    unsafe {
+        Ok(Vec::from_raw_parts(
+            values.as_mut_ptr() as *mut T,
+            values.len(),
+            values.capacity(),
+        ))
+    }
+}
+
+/// Converts a vector of ergonomic Rust values to a vector of FFI-safe [nicheless] ones.
+///
+/// [nicheless]: Nicheless
+pub fn into_vec<T: Iffi<U>, U: Nicheless>(safe: Vec<T>) -> Vec<U> {
+    crate::assert_same_size::<T, U>();
+    let mut safe = ManuallyDrop::new(safe);
+    // SAFETY: the Iffi trait guarantees that U is a superset of T with the same layout.
+    // the original vector is never dropped.
+    unsafe { Vec::from_raw_parts(safe.as_mut_ptr() as *mut U, safe.len(), safe.capacity()) }
+}
+
+/// Tries to convert a reference-counted FFI-safe [nicheless] value to a reference-counted ergonomic one.
+///
+/// The value is validated in place and the allocation is shared with the original [`Rc`].
+///
+/// [nicheless]: Nicheless
+pub fn try_from_rc<T: Iffi<U>, U: Nicheless>(value: Rc<U>) -> Result<Rc<T>, Error> {
+    T::can_transmute(&value)?;
+    crate::assert_same_size::<T, U>();
+    // SAFETY: T and U have the same size and alignment and the value is safe to transmute.
+    unsafe { Ok(Rc::from_raw(Rc::into_raw(value) as *const T)) }
+}
+
+/// Converts a reference-counted ergonomic Rust value to a reference-counted FFI-safe [nicheless] one.
+///
+/// [nicheless]: Nicheless
+pub fn into_rc<T: Iffi<U>, U: Nicheless>(safe: Rc<T>) -> Rc<U> {
+    crate::assert_same_size::<T, U>();
+    // SAFETY: the Iffi trait guarantees that U is a superset of T with the same layout.
+    unsafe { Rc::from_raw(Rc::into_raw(safe) as *const U) }
+}
+
+/// Tries to convert an atomically reference-counted FFI-safe [nicheless] value
+/// to an atomically reference-counted ergonomic one.
+///
+/// The value is validated in place and the allocation is shared with the original [`Arc`].
+///
+/// [nicheless]: Nicheless
+#[cfg(target_has_atomic = "ptr")]
+pub fn try_from_arc<T: Iffi<U>, U: Nicheless>(value: Arc<U>) -> Result<Arc<T>, Error> {
+    T::can_transmute(&value)?;
+    crate::assert_same_size::<T, U>();
+    // SAFETY: T and U have the same size and alignment and the value is safe to transmute.
+    unsafe { Ok(Arc::from_raw(Arc::into_raw(value) as *const T)) }
+}
+
+/// Converts an atomically reference-counted ergonomic Rust value
+/// to an atomically reference-counted FFI-safe [nicheless] one.
+///
+/// [nicheless]: Nicheless
+#[cfg(target_has_atomic = "ptr")]
+pub fn into_arc<T: Iffi<U>, U: Nicheless>(safe: Arc<T>) -> Arc<U> {
+    crate::assert_same_size::<T, U>();
+    // SAFETY: the Iffi trait guarantees that U is a superset of T with the same layout.
+    unsafe { Arc::from_raw(Arc::into_raw(safe) as *const U) }
+}
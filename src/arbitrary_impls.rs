@@ -0,0 +1,60 @@
+use core::mem::size_of;
+
+use arbitrary::{Arbitrary, Unstructured};
+
+use crate::{Iffi, MaybeInvalid};
+
+/// Potentially invalid values are made of arbitrary bytes.
+impl<'a, T> Arbitrary<'a> for MaybeInvalid<T> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let mut value = Self::zeroed();
+        // SAFETY: the value is zeroed, so every byte is initialized.
+        u.fill_buffer(unsafe { value.as_bytes_mut_unchecked() })?;
+        Ok(value)
+    }
+
+    fn size_hint(_: usize) -> (usize, Option<usize>) {
+        (size_of::<T>(), Some(size_of::<T>()))
+    }
+}
+
+/// Checks a value of `T` made of arbitrary bytes, for use in fuzz targets.
+///
+/// Panics if [`Iffi::can_transmute`] and [`Iffi::can_transmute_each`] disagree
+/// about whether the value is valid.
+/// When it is, the value is converted to `T` and back,
+/// so that tools like Miri and the sanitizers catch any undefined behaviour.
+///
+/// # Example
+/// ```rust
+/// # use core::num::NonZeroU32;
+/// #[derive(iffi::Iffi)]
+/// #[repr(C)]
+/// struct Header {
+///     id: NonZeroU32,
+///     flags: u16,
+/// }
+///
+/// // in a `cargo fuzz` target:
+/// // fuzz_target!(|data: &[u8]| { iffi::fuzz_iffi::<Header>(data); });
+/// iffi::fuzz_iffi::<Header>(&[1, 0, 0, 0, 2, 0, 0, 0]);
+/// iffi::fuzz_iffi::<Header>(&[0; 8]);
+/// ```
+pub fn fuzz_iffi<T: Iffi>(data: &[u8]) {
+    let Ok(value) = MaybeInvalid::<T>::arbitrary(&mut Unstructured::new(data)) else {
+        return;
+    };
+
+    let valid = T::can_transmute(&value).is_ok();
+    let mut errors = 0;
+    T::can_transmute_each(&value, &mut |_| errors += 1);
+    assert_eq!(
+        valid,
+        errors == 0,
+        "`can_transmute` and `can_transmute_each` disagree"
+    );
+
+    if let Ok(safe) = crate::try_from::<T, _>(value) {
+        let _ = crate::into::<T, MaybeInvalid<T>>(safe);
+    }
+}
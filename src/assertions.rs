@@ -0,0 +1,63 @@
+/// Asserts at compile time that `T` implements [`Iffi<U>`](crate::Iffi)
+/// and has the same size and alignment as `U`.
+///
+/// The universe defaults to [`MaybeInvalid<T>`](crate::MaybeInvalid).
+///
+/// Useful at the API boundaries of bindings crates,
+/// to lock in layout guarantees that foreign code relies upon.
+///
+/// # Example
+/// ```rust
+/// # use core::num::NonZeroU32;
+/// iffi::assert_iffi!(NonZeroU32, u32);
+/// iffi::assert_iffi!(NonZeroU32);
+/// ```
+#[macro_export]
+macro_rules! assert_iffi {
+    ($t:ty $(,)?) => {
+        $crate::assert_iffi!($t, $crate::MaybeInvalid<$t>);
+    };
+    ($t:ty, $u:ty $(,)?) => {
+        const _: () = {
+            const fn assert_iffi<T: $crate::Iffi<U>, U: $crate::Nicheless>() {}
+            assert_iffi::<$t, $u>();
+
+            assert!(
+                ::core::mem::size_of::<$t>() == ::core::mem::size_of::<$u>(),
+                concat!(
+                    "`",
+                    stringify!($t),
+                    "` must have the same size as `",
+                    stringify!($u),
+                    "`",
+                ),
+            );
+            assert!(
+                ::core::mem::align_of::<$t>() == ::core::mem::align_of::<$u>(),
+                concat!(
+                    "`",
+                    stringify!($t),
+                    "` must have the same alignment as `",
+                    stringify!($u),
+                    "`",
+                ),
+            );
+        };
+    };
+}
+
+/// Asserts at compile time that each of the given types is [`Nicheless`](crate::Nicheless).
+///
+/// # Example
+/// ```rust
+/// iffi::assert_nicheless!(u8, [u32; 4], iffi::MaybeInvalid<bool>);
+/// ```
+#[macro_export]
+macro_rules! assert_nicheless {
+    ($($t:ty),+ $(,)?) => {
+        const _: () = {
+            const fn assert_nicheless<T: $crate::Nicheless>() {}
+            $( assert_nicheless::<$t>(); )+
+        };
+    };
+}
@@ -0,0 +1,110 @@
+use core::fmt::{self, Write};
+
+use crate::BitPattern;
+
+impl BitPattern {
+    /// The number of bytes in the pattern.
+    pub fn len(&self) -> usize {
+        self.as_bytes().len()
+    }
+
+    /// Whether the pattern has no bytes, such as that of a zero-sized type.
+    pub fn is_empty(&self) -> bool {
+        self.as_bytes().is_empty()
+    }
+
+    /// Iterates over each bit of the pattern, least significant first.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use iffi::BitPattern;
+    /// let bits = BitPattern::from_ne(&0b101u8);
+    /// assert!(bits.iter_bits().take(3).eq([true, false, true]));
+    /// ```
+    pub fn iter_bits(&self) -> impl DoubleEndedIterator<Item = bool> + '_ {
+        self.as_bytes()
+            .iter()
+            .flat_map(|&byte| (0..8).map(move |bit| (byte >> bit) & 1 != 0))
+    }
+
+    /// The pattern as an unsigned integer,
+    /// or `None` if it has more bytes than a `u128`.
+    ///
+    /// Signed values are not sign-extended.
+    pub fn to_u128(&self) -> Option<u128> {
+        let bytes = self.as_bytes();
+        let mut buf = [0u8; 16];
+        buf.get_mut(..bytes.len())?.copy_from_slice(bytes);
+        Some(u128::from_le_bytes(buf))
+    }
+}
+
+/// Writes the digits of each byte, most significant first,
+/// padded to the width of the formatter like an integer.
+fn pad_digits(
+    f: &mut fmt::Formatter<'_>,
+    prefix: &str,
+    bytes: &[u8],
+    digits_per_byte: usize,
+    write_byte: impl Fn(&mut fmt::Formatter<'_>, u8) -> fmt::Result,
+) -> fmt::Result {
+    let len = prefix.len() + bytes.len() * digits_per_byte;
+    let padding = f.width().unwrap_or(0).saturating_sub(len);
+    let write_digits =
+        |f: &mut fmt::Formatter<'_>| bytes.iter().rev().try_for_each(|&byte| write_byte(f, byte));
+
+    // zero-padding goes between the prefix and the digits.
+    if f.sign_aware_zero_pad() {
+        f.write_str(prefix)?;
+        (0..padding).try_for_each(|_| f.write_char('0'))?;
+        return write_digits(f);
+    }
+
+    let (before, after) = match f.align() {
+        Some(fmt::Alignment::Left) => (0, padding),
+        Some(fmt::Alignment::Center) => (padding / 2, padding - padding / 2),
+        Some(fmt::Alignment::Right) | None => (padding, 0),
+    };
+    let fill = f.fill();
+    (0..before).try_for_each(|_| f.write_char(fill))?;
+    f.write_str(prefix)?;
+    write_digits(f)?;
+    (0..after).try_for_each(|_| f.write_char(fill))
+}
+
+/// Formats the pattern as a prefixed hexadecimal number, such as `0x00ff`,
+/// keeping the leading zeros of every byte.
+impl fmt::Display for BitPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        pad_digits(f, "0x", self.as_bytes(), 2, |f, byte| {
+            write!(f, "{byte:02x}")
+        })
+    }
+}
+
+impl fmt::LowerHex for BitPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let prefix = if f.alternate() { "0x" } else { "" };
+        pad_digits(f, prefix, self.as_bytes(), 2, |f, byte| {
+            write!(f, "{byte:02x}")
+        })
+    }
+}
+
+impl fmt::UpperHex for BitPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let prefix = if f.alternate() { "0x" } else { "" };
+        pad_digits(f, prefix, self.as_bytes(), 2, |f, byte| {
+            write!(f, "{byte:02X}")
+        })
+    }
+}
+
+impl fmt::Binary for BitPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let prefix = if f.alternate() { "0b" } else { "" };
+        pad_digits(f, prefix, self.as_bytes(), 8, |f, byte| {
+            write!(f, "{byte:08b}")
+        })
+    }
+}
@@ -0,0 +1,144 @@
+use core::{
+    mem::size_of,
+    num::{
+        NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128,
+        NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize,
+    },
+};
+
+use crate::{Error, ErrorKind, Iffi, MaybeInvalid};
+
+/// Types without padding bytes, whose every byte is always initialized.
+///
+/// Only these types can be viewed as raw bytes with [`as_ffi_bytes`],
+/// since reading uninitialized padding is undefined behaviour.
+///
+/// Implemented for primitives and arrays of them, and for structs
+/// that derive [`Iffi`] with the `#[iffi(deny_padding)]` attribute.
+///
+/// # Safety
+/// Every byte of every valid value of the type must be initialized.
+///
+/// [`Iffi`]: macro@crate::Iffi
+pub unsafe trait NoPadding: Sized {}
+
+macro_rules! impl_no_padding {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            // SAFETY: primitives have no padding.
+            unsafe impl NoPadding for $ty {}
+        )*
+    };
+}
+
+impl_no_padding! {
+    u8, u16, u32, u64, u128, usize,
+    i8, i16, i32, i64, i128, isize,
+    f32, f64, bool, char, (),
+    NonZeroU8, NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU128, NonZeroUsize,
+    NonZeroI8, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI128, NonZeroIsize,
+}
+
+// SAFETY: pointers are integers with provenance.
+unsafe impl<T> NoPadding for *const T {}
+// SAFETY: pointers are integers with provenance.
+unsafe impl<T> NoPadding for *mut T {}
+// SAFETY: arrays have no padding between their elements.
+unsafe impl<T: NoPadding, const N: usize> NoPadding for [T; N] {}
+// SAFETY: a valid `MaybeInvalid` is always initialized and has the same layout as T.
+unsafe impl<T: NoPadding> NoPadding for MaybeInvalid<T> {}
+
+/// Views the raw bytes of a value, such as to write it to a file or socket.
+///
+/// For a value that implements [`Iffi`], these are also the bytes of its universe.
+///
+/// # Example
+/// ```rust
+/// # use core::num::NonZeroU16;
+/// let value = NonZeroU16::new(7).unwrap();
+/// assert_eq!(iffi::as_ffi_bytes(&value), 7u16.to_ne_bytes());
+/// ```
+pub fn as_ffi_bytes<T: NoPadding>(value: &T) -> &[u8] {
+    // SAFETY: u8 has an alignment of 1 and every byte of the value is initialized.
+    unsafe { core::slice::from_raw_parts(value as *const T as *const u8, size_of::<T>()) }
+}
+
+/// Copies the raw bytes of a value into an array, such as to write it to a file or socket.
+///
+/// `N` must be the size of `T`, which is asserted at compile time.
+pub fn to_ffi_bytes<T: NoPadding, const N: usize>(value: &T) -> [u8; N] {
+    const {
+        assert!(
+            N == size_of::<T>(),
+            "the array must be the size of the value"
+        )
+    };
+    let mut bytes = [0u8; N];
+    bytes.copy_from_slice(as_ffi_bytes(value));
+    bytes
+}
+
+/// Tries to convert the raw bytes of a value, such as those read from a file or socket,
+/// to an ergonomic Rust type.
+///
+/// Returns [`ErrorKind::SizeMismatch`] unless there are exactly `size_of::<T>()` bytes.
+/// The bytes need not be aligned;
+/// when they are, they are validated in place before being copied.
+///
+/// # Example
+/// ```rust
+/// # use core::num::NonZeroU16;
+/// let value: NonZeroU16 = iffi::from_bytes(&7u16.to_ne_bytes()).unwrap();
+/// assert_eq!(value.get(), 7);
+/// assert!(iffi::from_bytes::<NonZeroU16>(&[0, 0]).is_err());
+/// assert!(iffi::from_bytes::<NonZeroU16>(&[1]).is_err());
+/// ```
+pub fn from_bytes<T: Iffi>(bytes: &[u8]) -> Result<T, Error> {
+    if bytes.len() != size_of::<T>() {
+        return Err(Error::new::<T, MaybeInvalid<T>>(ErrorKind::SizeMismatch {
+            expected: size_of::<T>(),
+            found: bytes.len(),
+        }));
+    }
+
+    let ptr = bytes.as_ptr() as *const MaybeInvalid<T>;
+    if ptr.is_aligned() {
+        // SAFETY: the pointer is aligned and valid for reads of the whole value,
+        // and any initialized bytes are a valid `MaybeInvalid`.
+        let value = unsafe { &*ptr };
+        T::can_transmute(value)?;
+        // SAFETY: the value is safe to transmute and has the same layout as T.
+        unsafe { Ok(core::ptr::read(value.as_ptr())) }
+    } else {
+        let mut value = MaybeInvalid::<T>::zeroed();
+        // SAFETY: the value is zeroed, so every byte is initialized.
+        unsafe { value.as_bytes_mut_unchecked() }.copy_from_slice(bytes);
+        crate::try_from(value)
+    }
+}
+
+/// Tries to convert the first `size_of::<T>()` bytes of a buffer to an ergonomic Rust type,
+/// returning it with the rest of the buffer.
+///
+/// Returns [`ErrorKind::SizeMismatch`] if the buffer is too short.
+///
+/// # Example
+/// ```rust
+/// # use core::num::NonZeroU16;
+/// let buf = [1, 0, 2, 0, 0xff];
+/// let (a, rest): (NonZeroU16, _) = iffi::try_from_bytes_prefix(&buf).unwrap();
+/// let (b, rest): (NonZeroU16, _) = iffi::try_from_bytes_prefix(rest).unwrap();
+/// assert_eq!((a.get(), b.get()), (u16::from_ne_bytes([1, 0]), u16::from_ne_bytes([2, 0])));
+/// assert_eq!(rest, [0xff]);
+/// ```
+pub fn try_from_bytes_prefix<T: Iffi>(bytes: &[u8]) -> Result<(T, &[u8]), Error> {
+    if bytes.len() < size_of::<T>() {
+        return Err(Error::new::<T, MaybeInvalid<T>>(ErrorKind::SizeMismatch {
+            expected: size_of::<T>(),
+            found: bytes.len(),
+        }));
+    }
+
+    let (prefix, rest) = bytes.split_at(size_of::<T>());
+    Ok((from_bytes(prefix)?, rest))
+}
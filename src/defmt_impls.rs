@@ -0,0 +1,92 @@
+use defmt::{write, Format, Formatter};
+
+use crate::{BitPattern, BitRanges, Error, ErrorKind, FieldPath};
+
+impl Format for BitPattern {
+    fn format(&self, f: Formatter) {
+        write!(f, "0x");
+        for byte in self.as_bytes().iter().rev() {
+            write!(f, "{=u8:02x}", byte);
+        }
+    }
+}
+
+impl Format for BitRanges {
+    fn format(&self, f: Formatter) {
+        write!(f, "[");
+        for (i, range) in self.ranges().enumerate() {
+            if i != 0 {
+                write!(f, ", ");
+            }
+            write!(f, "{}..={}", range.start(), range.end());
+        }
+        write!(f, "]");
+    }
+}
+
+impl Format for FieldPath {
+    fn format(&self, f: Formatter) {
+        if let Some(ty) = self.ty() {
+            write!(f, "{=str}", ty);
+        }
+        for field in self.fields() {
+            write!(f, ".{=str}", field);
+        }
+    }
+}
+
+impl Format for ErrorKind {
+    fn format(&self, f: Formatter) {
+        match self {
+            Self::NullPtr => write!(f, "Expected a pointer to not be null"),
+            Self::InvalidEnumDiscriminant { ty, bits, valid } => write!(
+                f,
+                "Invalid discriminant {} for enum {=str}; expected one of {}",
+                bits, ty, valid
+            ),
+            Self::InvalidBitPattern { bits, valid } => {
+                write!(
+                    f,
+                    "Invalid bit-pattern; {} not in the ranges {}",
+                    bits, valid
+                )
+            }
+            Self::ReservedBits { bits, mask } => write!(
+                f,
+                "Reserved bits are set; {} has bits outside of the mask {}",
+                bits, mask
+            ),
+            Self::Misaligned { required, found } => write!(
+                f,
+                "Misaligned pointer; expected an alignment of {=usize} but found {=usize}",
+                required, found
+            ),
+            Self::SizeMismatch { expected, found } => write!(
+                f,
+                "Size mismatch; expected {=usize} bytes but found {=usize}",
+                expected, found
+            ),
+            #[cfg(feature = "alloc")]
+            Self::InvalidElement { index, error } => {
+                write!(f, "Invalid element at index {=usize}; {}", index, **error)
+            }
+            // arbitrary errors can only be formatted with `core::fmt`.
+            #[cfg(feature = "alloc")]
+            Self::Custom(_) => write!(f, "Custom error"),
+        }
+    }
+}
+
+impl Format for Error {
+    fn format(&self, f: Formatter) {
+        write!(
+            f,
+            "Failed to convert {=str} into {=str}",
+            self.from, self.into
+        );
+        if !self.path.is_empty() {
+            write!(f, " at {}", self.path);
+        }
+        write!(f, "; {}", self.error);
+    }
+}
@@ -1,35 +1,124 @@
-use core::any::type_name;
-
-use thiserror_no_std::Error;
+use core::{
+    any::type_name,
+    fmt,
+    hash::{Hash, Hasher},
+};
 
 use crate::{BitPattern, BitRanges, Iffi, Nicheless};
 
 /// Potential errors converting with [`Iffi`].
-#[derive(Error, Debug)]
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum ErrorKind {
     /// A non-nullable pointer was found to be null.
-    #[error("Expected a pointer to not be null")]
     NullPtr,
-    /// Failed to find an enum variant that matches the discriminant.
-    #[error("Invalid enum discriminant {0}")]
-    InvalidEnumDiscriminant(BitPattern),
+    /// Failed to find a variant of the enum `ty` that matches the discriminant.
+    ///
+    /// When the discriminant matches but a field of the variant is invalid,
+    /// the variant is named in the error's [path](FieldPath) instead.
+    InvalidEnumDiscriminant {
+        ty: &'static str,
+        bits: BitPattern,
+        valid: BitRanges,
+    },
     /// A general error describing any invalid bit-pattern.
-    #[cfg_attr(
-        feature = "alloc",
-        error("Invalid bit-pattern; {bits} not in the ranges {valid}")
-    )]
-    #[cfg_attr(not(feature = "alloc"), error("Invalid bit-pattern; {bits}"))]
     InvalidBitPattern { bits: BitPattern, valid: BitRanges },
-    #[cfg(feature = "std")]
+    /// An integer had bits set outside of the bits that are meaningful for it.
+    ReservedBits { bits: BitPattern, mask: BitPattern },
+    /// A pointer required to be aligned to `required` bytes was only aligned to `found` bytes.
+    Misaligned { required: usize, found: usize },
+    /// A buffer of `found` bytes was converted to or from a type of `expected` bytes.
+    SizeMismatch { expected: usize, found: usize },
+    /// An element of a slice or collection failed to convert.
+    #[cfg(feature = "alloc")]
+    InvalidElement {
+        index: usize,
+        error: alloc::boxed::Box<ErrorKind>,
+    },
     /// Any other error.
-    #[error("{0}")]
-    Custom(alloc::boxed::Box<dyn std::error::Error>),
+    ///
+    /// Custom errors are all equal to one another,
+    /// and are cloned as an error with the same message.
+    #[cfg(feature = "alloc")]
+    Custom(
+        #[cfg_attr(
+            feature = "serde",
+            serde(serialize_with = "crate::serde_impls::display")
+        )]
+        alloc::boxed::Box<dyn core::error::Error>,
+    ),
+}
+
+impl Clone for ErrorKind {
+    fn clone(&self) -> Self {
+        match self {
+            Self::NullPtr => Self::NullPtr,
+            Self::InvalidEnumDiscriminant { ty, bits, valid } => Self::InvalidEnumDiscriminant {
+                ty,
+                bits: bits.clone(),
+                valid: valid.clone(),
+            },
+            Self::InvalidBitPattern { bits, valid } => Self::InvalidBitPattern {
+                bits: bits.clone(),
+                valid: valid.clone(),
+            },
+            Self::ReservedBits { bits, mask } => Self::ReservedBits {
+                bits: bits.clone(),
+                mask: mask.clone(),
+            },
+            Self::Misaligned { required, found } => Self::Misaligned {
+                required: *required,
+                found: *found,
+            },
+            Self::SizeMismatch { expected, found } => Self::SizeMismatch {
+                expected: *expected,
+                found: *found,
+            },
+            #[cfg(feature = "alloc")]
+            Self::InvalidElement { index, error } => Self::InvalidElement {
+                index: *index,
+                error: error.clone(),
+            },
+            #[cfg(feature = "alloc")]
+            Self::Custom(error) => {
+                use alloc::string::ToString;
+
+                Self::Custom(alloc::boxed::Box::new(Message(error.to_string())))
+            }
+        }
+    }
+}
+
+/// The message of a cloned custom error.
+#[cfg(feature = "alloc")]
+#[derive(Debug)]
+struct Message(alloc::string::String);
+
+#[cfg(feature = "alloc")]
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
 }
 
+#[cfg(feature = "alloc")]
+impl core::error::Error for Message {}
+
 impl PartialEq for ErrorKind {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
-            (Self::InvalidEnumDiscriminant(l), Self::InvalidEnumDiscriminant(r)) => l == r,
+            (
+                Self::InvalidEnumDiscriminant {
+                    ty: l_ty,
+                    bits: l_bits,
+                    valid: l_valid,
+                },
+                Self::InvalidEnumDiscriminant {
+                    ty: r_ty,
+                    bits: r_bits,
+                    valid: r_valid,
+                },
+            ) => l_ty == r_ty && l_bits == r_bits && l_valid == r_valid,
             (
                 Self::InvalidBitPattern {
                     bits: l_bits,
@@ -40,19 +129,257 @@ impl PartialEq for ErrorKind {
                     valid: r_valid,
                 },
             ) => l_bits == r_bits && l_valid == r_valid,
+            (
+                Self::ReservedBits {
+                    bits: l_bits,
+                    mask: l_mask,
+                },
+                Self::ReservedBits {
+                    bits: r_bits,
+                    mask: r_mask,
+                },
+            ) => l_bits == r_bits && l_mask == r_mask,
+            (
+                Self::Misaligned {
+                    required: l_required,
+                    found: l_found,
+                },
+                Self::Misaligned {
+                    required: r_required,
+                    found: r_found,
+                },
+            ) => l_required == r_required && l_found == r_found,
+            (
+                Self::SizeMismatch {
+                    expected: l_expected,
+                    found: l_found,
+                },
+                Self::SizeMismatch {
+                    expected: r_expected,
+                    found: r_found,
+                },
+            ) => l_expected == r_expected && l_found == r_found,
+            #[cfg(feature = "alloc")]
+            (
+                Self::InvalidElement {
+                    index: l_index,
+                    error: l_error,
+                },
+                Self::InvalidElement {
+                    index: r_index,
+                    error: r_error,
+                },
+            ) => l_index == r_index && l_error == r_error,
             _ => core::mem::discriminant(self) == core::mem::discriminant(other),
         }
     }
 }
 
+impl Eq for ErrorKind {}
+
+impl Hash for ErrorKind {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        core::mem::discriminant(self).hash(state);
+        match self {
+            Self::NullPtr => (),
+            Self::InvalidEnumDiscriminant { ty, bits, valid } => {
+                ty.hash(state);
+                bits.hash(state);
+                valid.hash(state);
+            }
+            Self::InvalidBitPattern { bits, valid } => {
+                bits.hash(state);
+                valid.hash(state);
+            }
+            Self::ReservedBits { bits, mask } => {
+                bits.hash(state);
+                mask.hash(state);
+            }
+            Self::Misaligned { required, found } => {
+                required.hash(state);
+                found.hash(state);
+            }
+            Self::SizeMismatch { expected, found } => {
+                expected.hash(state);
+                found.hash(state);
+            }
+            #[cfg(feature = "alloc")]
+            Self::InvalidElement { index, error } => {
+                index.hash(state);
+                error.hash(state);
+            }
+            // custom errors are all equal, so must all hash the same.
+            #[cfg(feature = "alloc")]
+            Self::Custom(_) => (),
+        }
+    }
+}
+
+/// A stable integer code for each kind of [`ErrorKind`],
+/// for reporting validation failures back across the C boundary.
+///
+/// Codes are never reused or renumbered. Zero is left free to mean success.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, crate::Iffi)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[iffi(crate = "crate")]
+#[repr(C)]
+pub enum ErrorCode {
+    /// See [`ErrorKind::NullPtr`].
+    NullPtr = 1,
+    /// See [`ErrorKind::InvalidEnumDiscriminant`].
+    InvalidEnumDiscriminant = 2,
+    /// See [`ErrorKind::InvalidBitPattern`].
+    InvalidBitPattern = 3,
+    /// See [`ErrorKind::ReservedBits`].
+    ReservedBits = 4,
+    /// See `ErrorKind::InvalidElement`, which requires the `"alloc"` feature.
+    InvalidElement = 5,
+    /// See `ErrorKind::Custom`, which requires the `"alloc"` feature.
+    Custom = 6,
+    /// See [`ErrorKind::Misaligned`].
+    Misaligned = 7,
+    /// See [`ErrorKind::SizeMismatch`].
+    SizeMismatch = 8,
+}
+
+impl ErrorKind {
+    /// The stable [`ErrorCode`] of this kind of error.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::NullPtr => ErrorCode::NullPtr,
+            Self::InvalidEnumDiscriminant { .. } => ErrorCode::InvalidEnumDiscriminant,
+            Self::InvalidBitPattern { .. } => ErrorCode::InvalidBitPattern,
+            Self::ReservedBits { .. } => ErrorCode::ReservedBits,
+            Self::Misaligned { .. } => ErrorCode::Misaligned,
+            Self::SizeMismatch { .. } => ErrorCode::SizeMismatch,
+            #[cfg(feature = "alloc")]
+            Self::InvalidElement { .. } => ErrorCode::InvalidElement,
+            #[cfg(feature = "alloc")]
+            Self::Custom(_) => ErrorCode::Custom,
+        }
+    }
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NullPtr => write!(f, "Expected a pointer to not be null"),
+            Self::InvalidEnumDiscriminant { ty, bits, valid } => write!(
+                f,
+                "Invalid discriminant {bits} for enum {ty}; expected one of {valid}"
+            ),
+            Self::InvalidBitPattern { bits, valid } => {
+                write!(f, "Invalid bit-pattern; {bits} not in the ranges {valid}")
+            }
+            Self::ReservedBits { bits, mask } => write!(
+                f,
+                "Reserved bits are set; {bits} has bits outside of the mask {mask}"
+            ),
+            Self::Misaligned { required, found } => write!(
+                f,
+                "Misaligned pointer; expected an alignment of {required} but found {found}"
+            ),
+            Self::SizeMismatch { expected, found } => {
+                write!(
+                    f,
+                    "Size mismatch; expected {expected} bytes but found {found}"
+                )
+            }
+            #[cfg(feature = "alloc")]
+            Self::InvalidElement { index, error } => {
+                write!(f, "Invalid element at index {index}; {error}")
+            }
+            #[cfg(feature = "alloc")]
+            Self::Custom(error) => fmt::Display::fmt(error, f),
+        }
+    }
+}
+
+impl core::error::Error for ErrorKind {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            #[cfg(feature = "alloc")]
+            Self::InvalidElement { error, .. } => Some(&**error),
+            // custom errors are transparent, so their source is our source.
+            #[cfg(feature = "alloc")]
+            Self::Custom(error) => error.source(),
+            _ => None,
+        }
+    }
+}
+
+/// The fields descended through to reach the value that failed to convert,
+/// displayed like `Packet.header.flags`.
+///
+/// Without the `"alloc"` feature no path is recorded.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct FieldPath {
+    /// The outermost type followed by each field, kept in one allocation
+    /// so as not to bloat every `Result` returning an [`Error`].
+    #[cfg(feature = "alloc")]
+    segments: alloc::vec::Vec<&'static str>,
+}
+
+impl FieldPath {
+    /// The name of the outermost type, if any field was descended through.
+    pub fn ty(&self) -> Option<&'static str> {
+        #[cfg(feature = "alloc")]
+        return self.segments.first().copied();
+        #[cfg(not(feature = "alloc"))]
+        return None;
+    }
+
+    /// The names of the fields descended through, outermost first.
+    pub fn fields(&self) -> &[&'static str] {
+        #[cfg(feature = "alloc")]
+        return self.segments.get(1..).unwrap_or_default();
+        #[cfg(not(feature = "alloc"))]
+        return &[];
+    }
+
+    /// Whether no fields were descended through.
+    pub fn is_empty(&self) -> bool {
+        self.fields().is_empty()
+    }
+}
+
+impl fmt::Display for FieldPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(ty) = self.ty() {
+            f.write_str(ty)?;
+        }
+        self.fields()
+            .iter()
+            .try_for_each(|field| write!(f, ".{field}"))
+    }
+}
+
 /// An error encountered while converting with [`Iffi`], wrapped with type name information.
-#[derive(Error, Debug, PartialEq)]
-#[error("Failed to convert {from} into {into}; {error}")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Error {
-    #[source]
     pub error: ErrorKind,
     pub from: &'static str,
     pub into: &'static str,
+    /// Where in the outermost type being converted the error occurred.
+    pub path: FieldPath,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Failed to convert {} into {}", self.from, self.into)?;
+        if !self.path.is_empty() {
+            write!(f, " at {}", self.path)?;
+        }
+        write!(f, "; {}", self.error)
+    }
+}
+
+impl core::error::Error for Error {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        Some(&self.error)
+    }
 }
 
 impl Error {
@@ -62,6 +389,57 @@ impl Error {
             error,
             from: type_name::<U>(),
             into: type_name::<T>(),
+            path: FieldPath::default(),
         }
     }
+
+    /// The stable [`ErrorCode`] of this error's [kind](ErrorKind).
+    pub fn code(&self) -> ErrorCode {
+        self.error.code()
+    }
+
+    /// Records that this error occurred within `field` of the type `T`,
+    /// prepending it to the error's [path](FieldPath).
+    ///
+    /// Called by the [`Iffi`] derive as errors propagate out of each field.
+    /// Without the `"alloc"` feature the field is discarded.
+    pub fn in_field<T: ?Sized>(self, field: &'static str) -> Self {
+        #[cfg(feature = "alloc")]
+        let path = {
+            let mut path = self.path;
+            match path.segments.first_mut() {
+                Some(ty) => {
+                    *ty = type_name::<T>();
+                    path.segments.insert(1, field);
+                }
+                None => path.segments.extend([type_name::<T>(), field]),
+            }
+            path
+        };
+        #[cfg(not(feature = "alloc"))]
+        let path = {
+            let _ = field;
+            self.path
+        };
+
+        Self { path, ..self }
+    }
+
+    /// Records that this error occurred in the element at `index` of a slice or collection.
+    ///
+    /// Without the `"alloc"` feature the index is discarded.
+    pub(crate) fn at_index(self, index: usize) -> Self {
+        #[cfg(feature = "alloc")]
+        let error = ErrorKind::InvalidElement {
+            index,
+            error: alloc::boxed::Box::new(self.error),
+        };
+        #[cfg(not(feature = "alloc"))]
+        let error = {
+            let _ = index;
+            self.error
+        };
+
+        Self { error, ..self }
+    }
 }
@@ -0,0 +1,165 @@
+#[cfg(feature = "alloc")]
+use core::{any::type_name, fmt};
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+#[cfg(feature = "alloc")]
+use crate::{Error, Iffi, MaybeInvalid, NoPadding};
+
+/// The name and position of a field of a type implementing [`Iffi`].
+///
+/// [`Iffi`]: crate::Iffi
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FieldLayout {
+    /// The name of the field, or its index for tuple structs.
+    pub name: &'static str,
+    /// The offset of the field in bytes.
+    pub offset: usize,
+    /// The size of the field in bytes.
+    pub size: usize,
+}
+
+/// A diagnostic report of a value, created by [`explain_invalid`].
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    /// The name of the type the value was checked against.
+    pub ty: &'static str,
+    /// The raw bytes of the whole value.
+    pub bytes: Vec<u8>,
+    /// Every field of the type, in declaration order.
+    pub fields: Vec<FieldReport>,
+    /// Errors that do not belong to any one field, such as a failed invariant.
+    pub errors: Vec<Error>,
+}
+
+/// A diagnostic report of a single field, part of a [`Report`].
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldReport {
+    /// The name and position of the field.
+    pub layout: FieldLayout,
+    /// The raw bytes of the field.
+    pub bytes: Vec<u8>,
+    /// Every error found in the field.
+    pub errors: Vec<Error>,
+}
+
+#[cfg(feature = "alloc")]
+impl Report {
+    /// Whether no errors were found anywhere in the value.
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty() && self.fields.iter().all(FieldReport::is_valid)
+    }
+
+    /// The fields of the value that are invalid.
+    pub fn invalid_fields(&self) -> impl Iterator<Item = &FieldReport> {
+        self.fields.iter().filter(|field| !field.is_valid())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl FieldReport {
+    /// Whether no errors were found in the field.
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+#[cfg(feature = "alloc")]
+fn write_bytes(f: &mut fmt::Formatter<'_>, bytes: &[u8]) -> fmt::Result {
+    bytes.iter().try_for_each(|byte| write!(f, "{byte:02x}"))
+}
+
+#[cfg(feature = "alloc")]
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} [", self.ty)?;
+        write_bytes(f, &self.bytes)?;
+        write!(
+            f,
+            "] is {}",
+            if self.is_valid() { "valid" } else { "invalid" }
+        )?;
+        for field in &self.fields {
+            write!(f, "\n  {} @ {}: ", field.layout.name, field.layout.offset)?;
+            write_bytes(f, &field.bytes)?;
+            if field.is_valid() {
+                write!(f, " ok")?;
+            }
+            for error in &field.errors {
+                write!(f, "\n    {error}")?;
+            }
+        }
+        for error in &self.errors {
+            write!(f, "\n  {error}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Checks every field of a potentially invalid value without stopping at the first error,
+/// reporting which fields are valid, which are not, and their raw bytes.
+///
+/// Fields are only known for types implementing [`Iffi::FIELDS`], such as derived structs.
+/// Errors in other types are reported for the value as a whole.
+///
+/// Only implemented for types with [no padding], since the bytes of the whole value are read.
+/// Derived structs can implement [`NoPadding`] with `#[iffi(deny_padding)]`.
+///
+/// # Example
+/// ```rust
+/// # use core::num::NonZeroU8;
+/// # use iffi::{Iffi, MaybeInvalid};
+/// #[derive(Iffi, Debug)]
+/// #[iffi(deny_padding)]
+/// #[repr(C)]
+/// struct Pair {
+///     a: NonZeroU8,
+///     b: NonZeroU8,
+/// }
+///
+/// let value: MaybeInvalid<Pair> = unsafe { core::mem::transmute([1u8, 0]) };
+/// let report = iffi::explain_invalid(&value);
+/// assert!(report.fields[0].is_valid());
+/// assert!(!report.fields[1].is_valid());
+/// assert_eq!(report.fields[1].bytes, [0]);
+/// ```
+///
+/// [no padding]: NoPadding
+#[cfg(feature = "alloc")]
+pub fn explain_invalid<T: Iffi + NoPadding>(value: &MaybeInvalid<T>) -> Report {
+    let bytes = value.as_bytes();
+    let mut fields: Vec<FieldReport> = T::FIELDS
+        .iter()
+        .map(|&layout| FieldReport {
+            layout,
+            bytes: bytes
+                .get(layout.offset..layout.offset + layout.size)
+                .unwrap_or_default()
+                .to_vec(),
+            errors: Vec::new(),
+        })
+        .collect();
+    let mut errors = Vec::new();
+
+    T::can_transmute_each(value, &mut |error| {
+        let field = error
+            .path
+            .fields()
+            .first()
+            .and_then(|name| fields.iter_mut().find(|field| field.layout.name == *name));
+        match field {
+            Some(field) => field.errors.push(error),
+            None => errors.push(error),
+        }
+    });
+
+    Report {
+        ty: type_name::<T>(),
+        bytes: bytes.to_vec(),
+        fields,
+        errors,
+    }
+}
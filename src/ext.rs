@@ -0,0 +1,46 @@
+use crate::{Error, Iffi, Nicheless};
+
+/// Method-call syntax for [`iffi::try_from`] and [`iffi::into`].
+///
+/// Implemented for all types.
+///
+/// # Example
+/// ```rust
+/// # use core::num::NonZeroU8;
+/// use iffi::IffiExt;
+///
+/// let safe = 5u8.try_into_safe::<NonZeroU8>().unwrap();
+/// assert_eq!(safe.into_ffi::<u8>(), 5);
+/// ```
+///
+/// [`iffi::try_from`]: crate::try_from
+/// [`iffi::into`]: crate::into
+pub trait IffiExt: Sized {
+    /// Tries to convert this FFI-safe [nicheless] value to a more ergonomic one.
+    ///
+    /// See [`iffi::try_from`].
+    ///
+    /// [nicheless]: Nicheless
+    /// [`iffi::try_from`]: crate::try_from
+    fn try_into_safe<T: Iffi<Self>>(self) -> Result<T, Error>
+    where
+        Self: Nicheless,
+    {
+        crate::try_from(self)
+    }
+
+    /// Converts this ergonomic value to an FFI-safe [nicheless] one.
+    ///
+    /// See [`iffi::into`].
+    ///
+    /// [nicheless]: Nicheless
+    /// [`iffi::into`]: crate::into
+    fn into_ffi<U: Nicheless>(self) -> U
+    where
+        Self: Iffi<U>,
+    {
+        crate::into(self)
+    }
+}
+
+impl<T> IffiExt for T {}
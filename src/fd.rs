@@ -0,0 +1,48 @@
+use std::os::fd::{AsRawFd, RawFd};
+
+use crate::{BitPattern, BitRanges, Error, ErrorKind, Iffi, MaybeInvalid};
+
+/// A raw file descriptor that is not -1.
+///
+/// Validated from a [`RawFd`], rejecting the -1 that POSIX functions return on failure.
+/// Only the bit-pattern is checked: whether the descriptor is open is not,
+/// so taking ownership is left to `unsafe { OwnedFd::from_raw_fd(fd.as_raw_fd()) }`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct ValidFd(RawFd);
+
+impl AsRawFd for ValidFd {
+    fn as_raw_fd(&self) -> RawFd {
+        self.0
+    }
+}
+
+fn valid_fd<U: crate::Nicheless>(fd: RawFd) -> Result<(), Error>
+where
+    ValidFd: Iffi<U>,
+{
+    if fd != -1 {
+        return Ok(());
+    }
+    Err(Error::new::<ValidFd, U>(ErrorKind::InvalidBitPattern {
+        bits: BitPattern::from_ne(&fd),
+        valid: BitRanges::from_ne(&[0u32..=0xffff_fffe]),
+    }))
+}
+
+// SAFETY: `ValidFd` is `#[repr(transparent)]` over a `RawFd` that is not -1.
+unsafe impl Iffi<RawFd> for ValidFd {
+    fn can_transmute(superset: &RawFd) -> Result<(), Error> {
+        valid_fd::<RawFd>(*superset)
+    }
+}
+
+// SAFETY: as above.
+unsafe impl Iffi<MaybeInvalid<Self>> for ValidFd {
+    fn can_transmute(superset: &MaybeInvalid<Self>) -> Result<(), Error> {
+        // SAFETY: `ValidFd` has the same layout as a `RawFd`,
+        // which is valid for any bit-pattern.
+        let fd = unsafe { *(superset.as_ptr() as *const RawFd) };
+        valid_fd::<MaybeInvalid<Self>>(fd)
+    }
+}
@@ -0,0 +1,11 @@
+use half::{bf16, f16};
+
+use crate::{Nicheless, NoPadding};
+
+// SAFETY: half-precision floats are plain 16-bit values, valid for any bit-pattern.
+unsafe impl Nicheless for f16 {}
+unsafe impl Nicheless for bf16 {}
+
+// SAFETY: as above, with no padding.
+unsafe impl NoPadding for f16 {}
+unsafe impl NoPadding for bf16 {}
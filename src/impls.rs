@@ -1,4 +1,4 @@
-use core::num::*;
+use core::{mem::ManuallyDrop, num::*, ptr::NonNull};
 
 extern crate std;
 
@@ -8,6 +8,8 @@ use crate::{BitPattern, BitRanges, Error, ErrorKind, Iffi, MaybeInvalid, Nichele
 
 // SAFETY: universe and type are the same.
 unsafe impl<U: Nicheless> Iffi<U> for U {
+    const TRIVIAL_CHECK: bool = true;
+
     fn can_transmute(_: &U) -> Result<(), Error> {
         Ok(())
     }
@@ -16,12 +18,75 @@ unsafe impl<U: Nicheless> Iffi<U> for U {
 // SAFETY: MaybeInvalid<T> is always valid
 // because T is nicheless. The types have the same layout.
 unsafe impl<U: Nicheless> Iffi<MaybeInvalid<U>> for U {
+    const TRIVIAL_CHECK: bool = true;
+
     fn can_transmute(_: &MaybeInvalid<U>) -> Result<(), Error> {
         Ok(())
     }
 }
 
-macro_rules! impl_nonzero_map {
+macro_rules! impl_transparent {
+    ($($wrapper:ident @ |$arg:ident| $inner:expr),+$(,)?) => {
+        $(
+            // SAFETY: the wrapper is `#[repr(transparent)]` over `T`,
+            // so it is valid exactly when the inner value is.
+            unsafe impl<T: Iffi> Iffi<$wrapper<MaybeInvalid<T>>> for $wrapper<T> {
+                const TRIVIAL_CHECK: bool = T::TRIVIAL_CHECK;
+                const FIELDS: &'static [crate::FieldLayout] = T::FIELDS;
+
+                fn can_transmute($arg: &$wrapper<MaybeInvalid<T>>) -> Result<(), Error> {
+                    T::can_transmute($inner)
+                }
+
+                fn can_transmute_each(
+                    $arg: &$wrapper<MaybeInvalid<T>>,
+                    report: &mut dyn FnMut(Error),
+                ) {
+                    T::can_transmute_each($inner, report)
+                }
+            }
+        )+
+    };
+}
+
+// the universe cannot be `Wrapping<U>` for any `T: Iffi<U>`,
+// as it would overlap with the reflexive implementation for nicheless wrappers.
+impl_transparent! {
+    Wrapping @ |superset| &superset.0,
+    Saturating @ |superset| &superset.0,
+    ManuallyDrop @ |superset| superset,
+}
+
+// SAFETY: an array of `MaybeInvalid<T>` has the same layout as an array of `T`,
+// and every element is checked.
+// the universe cannot be `[U; N]` for any `T: Iffi<U>`,
+// as it would overlap with the reflexive implementation for nicheless arrays.
+unsafe impl<T: Iffi, const N: usize> Iffi<[MaybeInvalid<T>; N]> for [T; N] {
+    const TRIVIAL_CHECK: bool = T::TRIVIAL_CHECK;
+
+    fn can_transmute(superset: &[MaybeInvalid<T>; N]) -> Result<(), Error> {
+        if T::TRIVIAL_CHECK {
+            return Ok(());
+        }
+        superset
+            .iter()
+            .enumerate()
+            .try_for_each(|(i, value)| T::can_transmute(value).map_err(|e| e.at_index(i)))
+    }
+
+    fn can_transmute_each(superset: &[MaybeInvalid<T>; N], report: &mut dyn FnMut(Error)) {
+        if T::TRIVIAL_CHECK {
+            return;
+        }
+        for (i, value) in superset.iter().enumerate() {
+            T::can_transmute_each(value, &mut |e| report(e.at_index(i)));
+        }
+    }
+}
+
+/// Implements `Iffi` for types with the same ABI as an integer,
+/// over both the integer and `MaybeInvalid<Self>`.
+macro_rules! impl_int_map {
     ($($ty:ty: $ty2:ty),+; $bytes:ident @ |$arg:pat_param| $fn:expr) => {
         $(
             const _: () = {
@@ -42,7 +107,7 @@ macro_rules! impl_nonzero_map {
                 unsafe impl Iffi<MaybeInvalid<$ty>> for $ty {
                     fn can_transmute(superset: &MaybeInvalid<$ty>) -> Result<(), Error> {
                         let ptr = superset.as_ptr() as *const $ty2;
-                        // SAFETY: NonZeroXXX and XXX have the same ABI
+                        // SAFETY: the type and the integer have the same ABI
                         let ty2 = unsafe { &*ptr };
                         from_universe::<MaybeInvalid<$ty>>(ty2)
                     }
@@ -52,7 +117,7 @@ macro_rules! impl_nonzero_map {
     };
 }
 
-impl_nonzero_map! {
+impl_int_map! {
     // SAFETY: non-zero types are the same size as their zeroable variants.
     // the only invalid variant for NonZero* is when they are zero.
     NonZeroU8: u8,
@@ -71,5 +136,86 @@ impl_nonzero_map! {
         bits: BitPattern::from_le(&[0u8; SIZE]),
         valid: BitRanges::from_le(&[concat_arrays!([1u8], [0u8; SIZE - 1])..=[0xffu8; SIZE]])
     })
+}
+
+impl_int_map! {
+    // SAFETY: `bool` is one byte and only 0 and 1 are valid.
+    bool: u8;
+    _SIZE @ |byte| (*byte <= 1).then_some(()).ok_or_else(|| ErrorKind::InvalidBitPattern {
+        bits: BitPattern::from_ne(byte),
+        valid: BitRanges::from_ne(&[0u8..=1]),
+    })
+}
+
+impl_int_map! {
+    // SAFETY: `char` is a `u32` that is a unicode scalar value.
+    char: u32;
+    _SIZE @ |code| char::from_u32(*code).map(|_| ()).ok_or_else(|| ErrorKind::InvalidBitPattern {
+        bits: BitPattern::from_ne(code),
+        valid: BitRanges::from_ne(&[0u32..=0xd7ff, 0xe000..=0x10ffff]),
+    })
+}
+
+impl_int_map! {
+    // SAFETY: `Ordering` is `#[repr(i8)]` with the discriminants -1, 0 and 1.
+    core::cmp::Ordering: i8;
+    _SIZE @ |tag| (-1..=1).contains(tag).then_some(()).ok_or_else(|| ErrorKind::InvalidEnumDiscriminant {
+        ty: core::any::type_name::<core::cmp::Ordering>(),
+        bits: BitPattern::from_ne(tag),
+        valid: BitRanges::from_ne(&[-1i8..=1]),
+    })
+}
 
+#[inline]
+fn non_null<T, U: Nicheless>(ptr: *const T) -> Result<(), Error>
+where
+    NonNull<T>: Iffi<U>,
+{
+    if ptr.is_null() {
+        Err(Error::new::<NonNull<T>, U>(ErrorKind::NullPtr))
+    } else {
+        Ok(())
+    }
+}
+
+// SAFETY: `NonNull<T>` is a `*mut T` that is not null.
+unsafe impl<T> Iffi<*mut T> for NonNull<T> {
+    fn can_transmute(superset: &*mut T) -> Result<(), Error> {
+        non_null::<T, *mut T>(*superset)
+    }
+}
+
+// SAFETY: `*const T` and `*mut T` have the same layout.
+unsafe impl<T> Iffi<*const T> for NonNull<T> {
+    fn can_transmute(superset: &*const T) -> Result<(), Error> {
+        non_null::<T, *const T>(*superset)
+    }
+}
+
+// SAFETY: a `MaybeInvalid<NonNull<T>>` is always a valid `*const T`.
+unsafe impl<T> Iffi<MaybeInvalid<NonNull<T>>> for NonNull<T> {
+    fn can_transmute(superset: &MaybeInvalid<NonNull<T>>) -> Result<(), Error> {
+        // SAFETY: the value is a pointer, which is valid for any bit-pattern.
+        let ptr = unsafe { *(superset.as_ptr() as *const *const T) };
+        non_null::<T, MaybeInvalid<NonNull<T>>>(ptr)
+    }
+}
+
+// SAFETY: `Option<NonNull<T>>` is a `*mut T` where null is `None`,
+// so every pointer is valid.
+unsafe impl<T> Iffi<*mut T> for Option<NonNull<T>> {
+    const TRIVIAL_CHECK: bool = true;
+
+    fn can_transmute(_: &*mut T) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+// SAFETY: `*const T` and `*mut T` have the same layout.
+unsafe impl<T> Iffi<*const T> for Option<NonNull<T>> {
+    const TRIVIAL_CHECK: bool = true;
+
+    fn can_transmute(_: &*const T) -> Result<(), Error> {
+        Ok(())
+    }
 }
@@ -0,0 +1,34 @@
+use std::io::{self, Read, Write};
+
+use crate::{Error, Iffi, MaybeInvalid, Nicheless, NoPadding};
+
+/// Reads the raw bytes of an FFI-safe [nicheless] value from a stream,
+/// such as a pipe or socket, and tries to convert it to an ergonomic one.
+///
+/// Returns an I/O error if the stream could not be read,
+/// or the conversion error if the value was read but is invalid.
+///
+/// # Example
+/// ```rust
+/// # use core::num::NonZeroU32;
+/// let mut stream = &7u32.to_ne_bytes()[..];
+/// let value: NonZeroU32 = iffi::read_ffi::<_, u32>(&mut stream).unwrap().unwrap();
+/// assert_eq!(value.get(), 7);
+/// ```
+///
+/// [nicheless]: Nicheless
+pub fn read_ffi<T: Iffi<U>, U: Nicheless>(reader: &mut impl Read) -> io::Result<Result<T, Error>> {
+    let mut value = MaybeInvalid::<U>::zeroed();
+    // SAFETY: the value is zeroed, so every byte is initialized.
+    reader.read_exact(unsafe { value.as_bytes_mut_unchecked() })?;
+    Ok(crate::try_from(value.into_inner()))
+}
+
+/// Writes the raw bytes of a value to a stream, such as a pipe or socket.
+///
+/// See [`as_ffi_bytes`].
+///
+/// [`as_ffi_bytes`]: crate::as_ffi_bytes
+pub fn write_ffi<T: NoPadding>(writer: &mut impl Write, value: &T) -> io::Result<()> {
+    writer.write_all(crate::as_ffi_bytes(value))
+}
@@ -0,0 +1,74 @@
+use core::{iter::FusedIterator, marker::PhantomData};
+
+use crate::{Error, Iffi, Nicheless};
+
+/// Extends iterators over FFI-safe [nicheless] values with [`Iffi`] conversions.
+///
+/// [nicheless]: Nicheless
+pub trait IffiIteratorExt: Iterator + Sized
+where
+    Self::Item: Nicheless,
+{
+    /// Lazily tries to convert each item of this iterator with [`iffi::try_from`].
+    ///
+    /// # Example
+    /// ```rust
+    /// # use core::num::NonZeroU8;
+    /// use iffi::IffiIteratorExt;
+    ///
+    /// let mut iter = [1u8, 0].into_iter().try_convert::<NonZeroU8>();
+    /// assert_eq!(iter.next(), Some(Ok(NonZeroU8::new(1).unwrap())));
+    /// assert!(matches!(iter.next(), Some(Err(_))));
+    /// ```
+    ///
+    /// [`iffi::try_from`]: crate::try_from
+    fn try_convert<T: Iffi<Self::Item>>(self) -> TryConvert<Self, T> {
+        TryConvert {
+            iter: self,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<I: Iterator> IffiIteratorExt for I where I::Item: Nicheless {}
+
+/// An iterator that tries to convert the items of another iterator.
+///
+/// Created by [`IffiIteratorExt::try_convert`].
+#[derive(Debug, Clone)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct TryConvert<I, T> {
+    iter: I,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<I: Iterator, T: Iffi<I::Item>> Iterator for TryConvert<I, T>
+where
+    I::Item: Nicheless,
+{
+    type Item = Result<T, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(crate::try_from)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I: DoubleEndedIterator, T: Iffi<I::Item>> DoubleEndedIterator for TryConvert<I, T>
+where
+    I::Item: Nicheless,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back().map(crate::try_from)
+    }
+}
+
+impl<I: ExactSizeIterator, T: Iffi<I::Item>> ExactSizeIterator for TryConvert<I, T> where
+    I::Item: Nicheless
+{
+}
+
+impl<I: FusedIterator, T: Iffi<I::Item>> FusedIterator for TryConvert<I, T> where I::Item: Nicheless {}
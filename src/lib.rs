@@ -1,5 +1,19 @@
 #![no_std]
 #![allow(clippy::drop_non_drop)]
+// without a heap, wider bit-patterns can only be stored inline,
+// so choosing a larger capacity knowingly makes every error larger.
+#![cfg_attr(
+    all(
+        not(feature = "alloc"),
+        any(
+            feature = "bit-pattern-32",
+            feature = "bit-pattern-64",
+            feature = "bit-pattern-128",
+            feature = "bit-pattern-256"
+        )
+    ),
+    allow(clippy::result_large_err)
+)]
 #![doc = include_str!("../README.md")]
 
 #[cfg(feature = "alloc")]
@@ -8,12 +22,9 @@ extern crate alloc;
 #[cfg(feature = "std")]
 extern crate std;
 
-use core::{
-    any::type_name,
-    mem::{size_of, size_of_val},
-};
+use core::mem::size_of;
 
-pub use iffi_macros::{Iffi, Nicheless};
+pub use iffi_macros::{mirror, Iffi, Nicheless, OneNiche};
 
 mod niche;
 pub use niche::*;
@@ -28,9 +39,89 @@ mod impls;
 mod bits;
 pub use bits::{BitPattern, BitRanges};
 
+mod bit_pattern;
+
 mod maybe_invalid;
 pub use maybe_invalid::*;
 
+mod ext;
+pub use ext::*;
+
+mod iter;
+pub use iter::*;
+
+mod bytes;
+pub use bytes::*;
+
+#[cfg(feature = "std")]
+mod io;
+#[cfg(feature = "std")]
+pub use io::*;
+
+#[cfg(all(feature = "std", any(unix, target_os = "wasi")))]
+mod fd;
+#[cfg(all(feature = "std", any(unix, target_os = "wasi")))]
+pub use fd::*;
+
+mod assertions;
+
+mod explain;
+pub use explain::*;
+
+mod pod;
+pub use pod::*;
+
+#[cfg(feature = "alloc")]
+mod alloc_conv;
+#[cfg(feature = "alloc")]
+pub use alloc_conv::*;
+
+#[cfg(feature = "serde")]
+mod serde_impls;
+#[cfg(feature = "serde")]
+pub use serde_impls::ViaUniverse;
+
+#[cfg(feature = "defmt")]
+mod defmt_impls;
+
+#[cfg(feature = "half")]
+mod half_impls;
+
+#[cfg(all(feature = "libc", unix))]
+mod libc_impls;
+
+#[cfg(feature = "abi_stable")]
+mod abi_stable_impls;
+
+#[cfg(feature = "arbitrary")]
+mod arbitrary_impls;
+#[cfg(feature = "arbitrary")]
+pub use arbitrary_impls::*;
+
+#[cfg(feature = "glam")]
+mod glam_impls;
+
+#[cfg(feature = "mint")]
+mod mint_impls;
+
+#[cfg(feature = "proptest")]
+mod proptest_impls;
+#[cfg(feature = "proptest")]
+pub use proptest_impls::*;
+
+#[cfg(feature = "uuid")]
+mod uuid_impls;
+
+#[cfg(feature = "windows")]
+mod windows_impls;
+#[cfg(feature = "windows")]
+pub use windows_impls::*;
+
+#[cfg(feature = "rayon")]
+mod par;
+#[cfg(feature = "rayon")]
+pub use par::*;
+
 /// The core trait of the `iffi` crate.
 ///
 /// This is typically implemented by deriving [`Iffi`],
@@ -44,56 +135,313 @@ pub use maybe_invalid::*;
 /// # Safety
 /// The implementation must guarantee that `Self` and `U`
 /// have *identical* layouts - the same size, alignment and ABI.
+/// Any bytes that are interior-mutable in one must be interior-mutable in the other,
+/// since references to each are reinterpreted as references to the other.
 ///
 /// [`can_transmute`] must return not return `Ok(())`
 /// unless `U` can safely be transmuted into `Self`.
 ///
+/// [`TRIVIAL_CHECK`] must not be `true` unless [`can_transmute`]
+/// returns `Ok(())` for every value of `U`.
+///
+/// [`can_transmute_each`] must report at least one error
+/// whenever [`can_transmute`] would not return `Ok(())`.
+///
 /// [universe]: crate#universe
 /// [`can_transmute`]: [`Iffi::can_transmute`]
+/// [`can_transmute_each`]: [`Iffi::can_transmute_each`]
+/// [`TRIVIAL_CHECK`]: [`Iffi::TRIVIAL_CHECK`]
 pub unsafe trait Iffi<U: Nicheless = MaybeInvalid<Self>> {
+    /// Whether [`can_transmute`] always succeeds, meaning every value of `U` is a valid `Self`.
+    ///
+    /// Bulk conversions like [`try_from_slice`] skip validating each element when this is `true`.
+    ///
+    /// [`can_transmute`]: Iffi::can_transmute
+    const TRIVIAL_CHECK: bool = false;
+
+    /// The name and position of each field of `Self`, used by [`explain_invalid`] for diagnostics.
+    ///
+    /// Derived implementations for structs list every field.
+    /// Other implementations have no fields by default.
+    ///
+    /// [`explain_invalid`]: crate::explain_invalid
+    const FIELDS: &'static [FieldLayout] = &[];
+
     /// Checks for the safety of transmuting `U` into `Self`.
     /// Returns `Ok(())` if the value is safe, and [`Err(iffi::Error)`] otherwise.
     ///
     /// [`Err(iffi::Error)`]: [`Error`]
     fn can_transmute(superset: &U) -> Result<(), Error>;
+
+    /// Like [`can_transmute`], but keeps checking after the first error
+    /// and passes every error found to `report`.
+    ///
+    /// The default implementation reports only the error of [`can_transmute`].
+    /// Derived implementations for structs report an error for every invalid field.
+    ///
+    /// [`can_transmute`]: Iffi::can_transmute
+    fn can_transmute_each(superset: &U, report: &mut dyn FnMut(Error)) {
+        if let Err(error) = Self::can_transmute(superset) {
+            report(error);
+        }
+    }
+}
+
+/// Asserts at compile time that a type and its universe are the same size,
+/// in case a manual implementation of [`Iffi`] is wrong.
+pub(crate) fn assert_same_size<T, U>() {
+    const {
+        assert!(
+            size_of::<T>() == size_of::<U>(),
+            "a type and its universe must be the same size"
+        )
+    }
 }
 
 /// Tries to convert an FFI-safe [nicheless] type to a more ergonomic one.
 ///
 /// [nicheless]: Nicheless
-pub fn try_from<T: Iffi<U>, U: Nicheless + core::fmt::Debug>(value: U) -> Result<T, Error> {
+pub fn try_from<T: Iffi<U>, U: Nicheless>(value: U) -> Result<T, Error> {
     T::can_transmute(&value)?;
-    debug_assert_eq!(
-        size_of_val(&value),
-        size_of::<T>(),
-        "tried converting from {} to {} but they are different sizes!",
-        type_name::<U>(),
-        type_name::<T>(),
-    );
+    assert_same_size::<T, U>();
     // SAFETY: the superset and the subset are the same size and value is safe to transmute.
     unsafe { Ok(transmute::transmute(value)) }
 }
 
+/// Tries to convert a borrowed FFI-safe [nicheless] value to a more ergonomic one.
+///
+/// The value is validated in place and only read once it is known to be valid,
+/// so large values are never copied onto the stack before being returned.
+/// To avoid the stack entirely, see [`try_from_ref_boxed`].
+///
+/// [nicheless]: Nicheless
+/// [`try_from_ref_boxed`]: crate::try_from_ref_boxed
+pub fn try_from_ref<T: Iffi<U> + Copy, U: Nicheless>(value: &U) -> Result<T, Error> {
+    T::can_transmute(value)?;
+    // SAFETY: T and U have the same layout and the value is safe to transmute.
+    // T is `Copy` so reading it out of the borrowed value cannot duplicate ownership.
+    unsafe { Ok(core::ptr::read(value as *const U as *const T)) }
+}
+
+/// Tries to convert a borrowed FFI-safe [nicheless] value to a more ergonomic one,
+/// copying it only once it is known to be valid.
+///
+/// Unlike [`try_from_ref`], `T` need not be `Copy`,
+/// so this suits cheap universes like integers and raw pointers.
+///
+/// # Example
+/// ```rust
+/// # use core::num::NonZeroU16;
+/// let raw = [1u16, 0, 3];
+/// let first: NonZeroU16 = iffi::try_from_copy(&raw[0]).unwrap();
+/// assert_eq!(first.get(), 1);
+/// assert!(iffi::try_from_copy::<NonZeroU16, _>(&raw[1]).is_err());
+/// ```
+///
+/// [nicheless]: Nicheless
+pub fn try_from_copy<T: Iffi<U>, U: Nicheless + Copy>(value: &U) -> Result<T, Error> {
+    T::can_transmute(value)?;
+    // SAFETY: T and U have the same layout and the value is safe to transmute.
+    // U is `Copy` so the copy of the value owns nothing the original does not.
+    unsafe { Ok(transmute::transmute(*value)) }
+}
+
+/// Tries to convert an [`AtomicU8`] holding `0` or `1` to an [`AtomicBool`].
+///
+/// `AtomicBool` does not implement [`Iffi`] because it cannot be validated behind a shared reference:
+/// another thread could store an invalid value after the check.
+/// Taking the atomic by value rules that out.
+///
+/// [`AtomicU8`]: core::sync::atomic::AtomicU8
+/// [`AtomicBool`]: core::sync::atomic::AtomicBool
+#[cfg(feature = "atomics")]
+pub fn try_atomic_bool_from(
+    value: core::sync::atomic::AtomicU8,
+) -> Result<core::sync::atomic::AtomicBool, Error> {
+    try_from::<bool, u8>(value.into_inner())
+        .map(core::sync::atomic::AtomicBool::new)
+        .map_err(|error| Error {
+            from: core::any::type_name::<core::sync::atomic::AtomicU8>(),
+            into: core::any::type_name::<core::sync::atomic::AtomicBool>(),
+            ..error
+        })
+}
+
+/// Tries to read an FFI-safe [nicheless] value from a raw pointer
+/// and convert it to a more ergonomic one.
+///
+/// Returns [`ErrorKind::NullPtr`] or [`ErrorKind::Misaligned`]
+/// rather than reading through a null or misaligned pointer.
+///
+/// # Safety
+///
+/// If `ptr` is non-null and aligned, it must be valid for reads of `U`.
+/// As with [`ptr::read`], the value is copied out of the pointer,
+/// so the caller must not use it again if `T` is not `Copy`.
+///
+/// [nicheless]: Nicheless
+/// [`ptr::read`]: core::ptr::read
+pub unsafe fn try_from_ptr<T: Iffi<U>, U: Nicheless>(ptr: *const U) -> Result<T, Error> {
+    if ptr.is_null() {
+        return Err(Error::new::<T, U>(ErrorKind::NullPtr));
+    }
+    check_aligned(ptr).map_err(Error::new::<T, U>)?;
+
+    // SAFETY: the pointer is non-null, aligned and, by the caller, valid for reads.
+    T::can_transmute(unsafe { &*ptr })?;
+    // SAFETY: T and U have the same layout and the value is safe to transmute.
+    unsafe { Ok(core::ptr::read(ptr as *const T)) }
+}
+
+fn check_aligned<T>(ptr: *const T) -> Result<(), ErrorKind> {
+    if ptr.is_aligned() {
+        return Ok(());
+    }
+    let addr = ptr as usize;
+    Err(ErrorKind::Misaligned {
+        required: core::mem::align_of::<T>(),
+        // the lowest set bit of the address.
+        found: addr & addr.wrapping_neg(),
+    })
+}
+
+/// Converts a nullable raw pointer from foreign code to an optional reference,
+/// checking that it is aligned.
+///
+/// A null pointer is `None`, and a misaligned one is an [`ErrorKind::Misaligned`] error.
+///
+/// # Safety
+///
+/// Only null and alignment are checked.
+/// If `ptr` is non-null, it must point to a valid `T`
+/// that is not mutated for the lifetime `'a`, as with [`pointer::as_ref`].
+///
+/// [`pointer::as_ref`]: https://doc.rust-lang.org/std/primitive.pointer.html#method.as_ref
+pub unsafe fn try_option_ref_from_ptr<'a, T>(ptr: *const T) -> Result<Option<&'a T>, Error> {
+    check_aligned(ptr).map_err(|error| Error {
+        error,
+        from: core::any::type_name::<*const T>(),
+        into: core::any::type_name::<Option<&T>>(),
+        path: FieldPath::default(),
+    })?;
+    // SAFETY: the pointer is aligned and, by the caller, null or valid for `'a`.
+    unsafe { Ok(ptr.as_ref()) }
+}
+
+/// Converts a raw pointer from foreign code to a reference,
+/// checking that it is non-null and aligned.
+///
+/// Returns [`ErrorKind::NullPtr`] or [`ErrorKind::Misaligned`]
+/// rather than creating a null or misaligned reference.
+///
+/// # Safety
+///
+/// Only null and alignment are checked; the validity of the pointee is the caller's responsibility.
+/// If `ptr` is non-null and aligned, it must point to a valid `T`
+/// that is not mutated for the lifetime `'a`.
+///
+/// # Example
+/// ```rust
+/// extern "C" fn callback(data: *const u32) -> u32 {
+///     // SAFETY: the caller passes a valid `u32` or null.
+///     match unsafe { iffi::try_ref_from_ptr(data) } {
+///         Ok(data) => *data,
+///         Err(_) => 0,
+///     }
+/// }
+///
+/// assert_eq!(callback(&7), 7);
+/// assert_eq!(callback(core::ptr::null()), 0);
+/// ```
+pub unsafe fn try_ref_from_ptr<'a, T>(ptr: *const T) -> Result<&'a T, Error> {
+    // SAFETY: upheld by the caller.
+    unsafe { try_option_ref_from_ptr(ptr) }?.ok_or_else(|| Error {
+        error: ErrorKind::NullPtr,
+        from: core::any::type_name::<*const T>(),
+        into: core::any::type_name::<&T>(),
+        path: FieldPath::default(),
+    })
+}
+
+/// Checks an FFI-safe [nicheless] value in place,
+/// returning a shared reference to it as a more ergonomic type.
+///
+/// Unlike [`try_from`], the value is never moved or copied.
+///
+/// # Example
+/// ```rust
+/// # use core::num::NonZeroU8;
+/// # use iffi::MaybeInvalid;
+/// let value = MaybeInvalid::new(NonZeroU8::new(3).unwrap());
+/// let valid: &NonZeroU8 = iffi::validate(&value).unwrap();
+/// assert_eq!(valid.get(), 3);
+/// ```
+///
+/// [nicheless]: Nicheless
+pub fn validate<T: Iffi<U>, U: Nicheless>(value: &U) -> Result<&T, Error> {
+    T::can_transmute(value)?;
+    // SAFETY: T and U have the same layout and the value is safe to transmute.
+    unsafe { Ok(&*(value as *const U as *const T)) }
+}
+
 /// Converts an ergonomic Rust type to an FFI-safe [nicheless] type.
 ///
 /// [nicheless]: Nicheless
 pub fn into<T: Iffi<U>, U: Nicheless>(safe: T) -> U {
-    debug_assert_eq!(
-        size_of::<U>(),
-        size_of_val(&safe),
-        "tried converting {} into {} but they are different sizes!",
-        type_name::<T>(),
-        type_name::<U>(),
-    );
+    assert_same_size::<T, U>();
     // SAFETY: the Iffi trait guarantees that T::Univserse is a superset of T
     unsafe { transmute::transmute(safe) }
 }
 
+/// Converts a reference to an ergonomic Rust type to a reference to an FFI-safe [nicheless] type.
+///
+/// Useful for passing a borrowed value to a foreign function taking a pointer to the raw layout.
+///
+/// [nicheless]: Nicheless
+pub fn into_ref<T: Iffi<U>, U: Nicheless>(safe: &T) -> &U {
+    // SAFETY: the Iffi trait guarantees that U is a superset of T with the same layout.
+    unsafe { &*(safe as *const T as *const U) }
+}
+
+/// Converts a mutable reference to an ergonomic Rust type
+/// to a mutable reference to an FFI-safe [nicheless] type.
+///
+/// # Safety
+///
+/// Whatever is written through the returned reference,
+/// including by a foreign function, must be a valid `T`
+/// by the time the reference is dropped.
+/// Fields may only be set to values that [`Iffi::can_transmute`] would accept.
+///
+/// [nicheless]: Nicheless
+pub unsafe fn into_mut<T: Iffi<U>, U: Nicheless>(safe: &mut T) -> &mut U {
+    // SAFETY: the Iffi trait guarantees that U is a superset of T with the same layout,
+    // and the caller guarantees that the value remains a valid T.
+    unsafe { &mut *(safe as *mut T as *mut U) }
+}
+
+/// Tries to convert a slice of FFI-safe [nicheless] values to a slice of ergonomic ones.
+///
+/// Every element is validated and the slice is reinterpreted in place.
+///
+/// [nicheless]: Nicheless
+pub fn try_from_slice<T: Iffi<U>, U: Nicheless>(values: &[U]) -> Result<&[T], Error> {
+    if !T::TRIVIAL_CHECK {
+        values
+            .iter()
+            .enumerate()
+            .try_for_each(|(i, value)| T::can_transmute(value).map_err(|e| e.at_index(i)))?;
+    }
+    // SAFETY: T and U have the same layout and every element is safe to transmute.
+    unsafe { Ok(&*(values as *const [U] as *const [T])) }
+}
+
 #[cfg(test)]
 mod tests {
     use core::{
+        any::type_name,
         marker::PhantomData,
-        num::{NonZeroU32, NonZeroU8},
+        num::{NonZeroU16, NonZeroU32, NonZeroU8},
     };
 
     use crate::{self as iffi, *};
@@ -126,123 +474,1568 @@ mod tests {
             bits: BitPattern::from_le(&[0u8; 4]),
             valid: BitRanges::from_le(&[1u32..=0xffffffff])
         });
+
+        assert_fails!(bool = 2u8 => ErrorKind::InvalidBitPattern {
+            bits: BitPattern::from_ne(&2u8),
+            valid: BitRanges::from_ne(&[0u8..=1])
+        });
+
+        assert_fails!(char = 0xd800u32 => ErrorKind::InvalidBitPattern {
+            bits: BitPattern::from_ne(&0xd800u32),
+            valid: BitRanges::from_ne(&[0u32..=0xd7ff, 0xe000..=0x10ffff])
+        });
+        assert!(try_from::<char, u32>(0x110000).is_err());
+
+        assert_fails!(core::cmp::Ordering = 2i8 => ErrorKind::InvalidEnumDiscriminant {
+            ty: type_name::<core::cmp::Ordering>(),
+            bits: BitPattern::from_ne(&2i8),
+            valid: BitRanges::from_ne(&[-1i8..=1])
+        });
     }
 
     #[test]
-    fn derive_iffi() {
-        #[derive(Iffi)]
-        #[repr(C)]
-        struct A {
-            a: u8,
-            b: NonZeroU8,
-            #[iffi(with = "u8")]
-            c: u8,
-        }
+    fn primitives() {
+        assert_eq!(try_from::<bool, u8>(1), Ok(true));
+        assert_eq!(into::<bool, u8>(false), 0);
+        assert_eq!(try_from(MaybeInvalid::new(true)), Ok(true));
+        assert_eq!(try_from::<char, u32>(0x1f980), Ok('🦀'));
+        assert_eq!(into::<char, u32>('a'), 0x61);
+        assert_eq!(try_from::<_, i8>(-1), Ok(core::cmp::Ordering::Less));
+        assert_eq!(into::<_, i8>(core::cmp::Ordering::Greater), 1);
 
-        #[derive(Iffi, Clone, Copy, PartialEq, Debug)]
-        #[repr(isize, align(1024))]
-        enum TA {
-            A,
-            B,
-            D(u32) = 150,
-            E { a: u16, b: NonZeroU8 },
-        }
+        #[cfg(feature = "atomics")]
+        {
+            use core::sync::atomic::{AtomicBool, AtomicU8, Ordering};
 
-        roundtrip!(TA::A);
-        roundtrip!(TA::B);
-        roundtrip!(TA::D(5));
-        roundtrip!(TA::E {
-            a: 100,
-            b: NonZeroU8::new(3).unwrap(),
-        });
+            let flag: AtomicBool = try_atomic_bool_from(AtomicU8::new(1)).unwrap();
+            assert!(flag.load(Ordering::Relaxed));
+            assert!(try_atomic_bool_from(AtomicU8::new(2)).is_err());
+        }
     }
 
     #[test]
-    fn derive_generics() {
-        #[derive(Iffi)]
+    fn arrays() {
+        let ids: [MaybeInvalid<NonZeroU8>; 3] = unsafe { core::mem::transmute([1u8, 2, 0]) };
+        let error = try_from::<[NonZeroU8; 3], _>(ids).unwrap_err();
+        #[cfg(feature = "alloc")]
+        assert!(matches!(
+            error.error,
+            ErrorKind::InvalidElement { index: 2, .. }
+        ));
+        #[cfg(not(feature = "alloc"))]
+        let _ = error;
+
+        #[derive(Iffi, Debug, PartialEq)]
+        #[iffi(crate = "crate")]
         #[repr(C)]
-        struct A<T: Iffi, U> {
-            b: T,
-            a: PhantomData<U>,
+        struct Ids {
+            #[iffi(with = "[MaybeInvalid<NonZeroU8>; 2]")]
+            ids: [NonZeroU8; 2],
         }
+
+        let ids = [NonZeroU8::new(3).unwrap(), NonZeroU8::new(4).unwrap()];
+        let raw: [MaybeInvalid<NonZeroU8>; 2] = unsafe { core::mem::transmute([3u8, 4]) };
+        assert_eq!(try_from::<[NonZeroU8; 2], _>(raw), Ok(ids));
+        roundtrip!(Ids { ids });
     }
 
     #[test]
-    fn nested() {
-        #[derive(Iffi, PartialEq, Debug)]
-        #[repr(C)]
-        struct A;
+    fn wrappers() {
+        use core::num::{Saturating, Wrapping};
 
-        #[derive(Iffi, PartialEq, Debug)]
-        #[repr(C)]
-        struct B(A);
+        let zero = Wrapping(MaybeInvalid::<NonZeroU8>::zeroed());
+        assert!(try_from::<Wrapping<NonZeroU8>, _>(zero).is_err());
+        let one = Saturating(MaybeInvalid::new(NonZeroU8::MIN));
+        assert_eq!(try_from(one), Ok(Saturating(NonZeroU8::MIN)));
+        assert_nicheless!(Saturating<u32>, core::mem::ManuallyDrop<u64>);
+        assert_nicheless!(core::cell::Cell<u32>, core::cell::UnsafeCell<[u8; 2]>);
+        assert_nicheless!((), PhantomData<u8>, core::marker::PhantomPinned);
+        #[cfg(feature = "half")]
+        assert_nicheless!(half::f16, [half::bf16; 4]);
+        #[cfg(all(feature = "libc", unix))]
+        assert_nicheless!(libc::timespec, libc::iovec, libc::sockaddr_storage);
+        #[cfg(feature = "windows")]
+        {
+            assert_nicheless!(windows_sys::core::GUID, Option<WinHandle>);
+            assert_eq!(try_from::<WinBool, i32>(1).map(bool::from), Ok(true));
+            assert!(try_from::<WinBool, i32>(-1).is_err());
+            let mut value = 0u8;
+            let handle: WinHandle = try_from(&mut value as *mut u8 as *mut _).unwrap();
+            assert_eq!(handle.as_raw() as *const u8, &value as *const u8);
+            assert!(try_from::<WinHandle, _>(core::ptr::null_mut()).is_err());
+        }
+        #[cfg(feature = "uuid")]
+        assert_eq!(as_ffi_bytes(&uuid::Uuid::nil()), [0; 16]);
+        #[cfg(feature = "glam")]
+        assert_nicheless!(glam::Vec3A, glam::Mat4, glam::DQuat);
+        #[cfg(feature = "mint")]
+        assert_nicheless!(mint::Vector3<f32>, mint::ColumnMatrix4<f64>);
+        #[cfg(feature = "abi_stable")]
+        {
+            use abi_stable::std_types::{RNone, ROption, RSome};
 
-        #[derive(Iffi, PartialEq, Debug)]
-        #[repr(C)]
-        struct C {
-            b: B,
+            let some: MaybeInvalid<ROption<NonZeroU8>> = unsafe { core::mem::transmute([0u8, 1]) };
+            assert_eq!(try_from(some), Ok(RSome(NonZeroU8::MIN)));
+            let none: MaybeInvalid<ROption<NonZeroU8>> = unsafe { core::mem::transmute([1u8, 0]) };
+            assert_eq!(try_from(none), Ok(RNone));
+            let zero: MaybeInvalid<ROption<NonZeroU8>> = MaybeInvalid::zeroed();
+            assert!(try_from::<ROption<NonZeroU8>, _>(zero).is_err());
+            let tag: MaybeInvalid<ROption<NonZeroU8>> = unsafe { core::mem::transmute([2u8, 1]) };
+            assert!(try_from::<ROption<NonZeroU8>, _>(tag).is_err());
         }
+        #[cfg(all(feature = "arch-simd", target_arch = "x86_64"))]
+        assert_nicheless!(core::arch::x86_64::__m128, [core::arch::x86_64::__m256i; 2]);
+        assert_eq!(try_from::<(), ()>(()), Ok(()));
 
-        #[derive(Iffi, PartialEq, Debug)]
-        #[repr(u8)]
-        enum D {
-            A(A),
-            B(B),
-            C(C),
-            D { c: C },
-            E,
+        #[derive(Nicheless)]
+        #[iffi(crate = "crate")]
+        #[repr(C)]
+        union Value {
+            bytes: core::mem::ManuallyDrop<[u8; 4]>,
+            raw: u32,
         }
+        assert_nicheless!(Value);
+        let dropped = core::mem::ManuallyDrop::new(MaybeInvalid::<NonZeroU32>::zeroed());
+        assert!(try_from::<core::mem::ManuallyDrop<NonZeroU32>, _>(dropped).is_err());
+    }
 
-        roundtrip!(A);
-        roundtrip!(B(A));
-        roundtrip!(C { b: B(A) });
-        roundtrip!(D::A(A));
-        roundtrip!(D::B(B(A)));
-        roundtrip!(D::C(C { b: B(A) }));
-        roundtrip!(D::D { c: C { b: B(A) } });
-        roundtrip!(D::E);
+    #[test]
+    fn pointers() {
+        use core::ptr::{null_mut, NonNull};
 
-        #[derive(Iffi, PartialEq, Debug, Clone, Copy)]
-        #[repr(C)]
-        struct Deep1(NonZeroU8);
-        #[derive(Iffi, PartialEq, Debug, Clone, Copy)]
-        #[repr(C)]
-        struct Deep2(Deep1);
-        #[derive(Iffi, PartialEq, Debug, Clone, Copy)]
-        #[repr(C)]
-        struct Deep3(Deep2);
-        #[derive(Iffi, PartialEq, Debug, Clone, Copy)]
+        let mut value = 5u32;
+        let ptr: *mut u32 = &mut value;
+        assert_eq!(try_from(ptr), Ok(NonNull::new(ptr).unwrap()));
+        assert_eq!(into::<NonNull<u32>, *const u32>(NonNull::from(&value)), ptr);
+        assert_fails!(NonNull<u32> = null_mut::<u32>() => ErrorKind::NullPtr);
+        assert!(try_from::<NonNull<u32>, _>(MaybeInvalid::zeroed()).is_err());
+
+        assert_eq!(try_from(null_mut::<u32>()), Ok(None::<NonNull<u32>>));
+        assert_eq!(try_from(ptr), Ok(NonNull::new(ptr)));
+        let raw = [0u32; 2];
+        let misaligned = (raw.as_ptr() as *const u8).wrapping_add(2) as *const u32;
+        assert_eq!(
+            unsafe { try_option_ref_from_ptr(raw.as_ptr()) },
+            Ok(Some(&0))
+        );
+        assert_eq!(
+            unsafe { try_option_ref_from_ptr::<u32>(core::ptr::null()) },
+            Ok(None)
+        );
+        assert_eq!(
+            unsafe { try_option_ref_from_ptr(misaligned) }.map_err(|e| e.error),
+            Err(ErrorKind::Misaligned {
+                required: 4,
+                found: 2
+            })
+        );
+    }
+
+    #[test]
+    fn error_codes() {
+        let error = try_from::<NonZeroU8, u8>(0).unwrap_err();
+        assert_eq!(error.code(), ErrorCode::InvalidBitPattern);
+        assert_eq!(ErrorCode::InvalidBitPattern as core::ffi::c_int, 3);
+        assert_eq!(ErrorKind::NullPtr.code(), ErrorCode::NullPtr);
+
+        let code: MaybeInvalid<ErrorCode> = unsafe { core::mem::transmute(6 as core::ffi::c_int) };
+        assert_eq!(try_from(code), Ok(ErrorCode::Custom));
+        assert!(try_from::<ErrorCode, _>(MaybeInvalid::zeroed()).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde() {
+        let error = try_from::<NonZeroU16, u16>(0).unwrap_err();
+        let json = serde_json::to_value(&error).unwrap();
+        assert_eq!(
+            json["error"]["InvalidBitPattern"]["bits"],
+            serde_json::json!([0, 0])
+        );
+        assert_eq!(json["into"], type_name::<NonZeroU16>());
+
+        let bits = BitPattern::from_le(&0x1234u16);
+        let json = serde_json::to_string(&bits).unwrap();
+        assert_eq!(json, "[52,18]");
+        assert_eq!(serde_json::from_str::<BitPattern>(&json).unwrap(), bits);
+
+        let ranges = BitRanges::from_le(&[1u8..=0xff]);
+        let json = serde_json::to_string(&ranges).unwrap();
+        assert_eq!(serde_json::from_str::<BitRanges>(&json).unwrap(), ranges);
+    }
+
+    #[test]
+    fn error_source() {
+        use core::error::Error as _;
+
+        let error = try_from::<NonZeroU8, u8>(0).unwrap_err();
+        let source = error.source().unwrap();
+        assert!(source.is::<ErrorKind>());
+        assert!(source.source().is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn accumulated_errors() {
+        #[derive(Iffi, Clone, Copy, PartialEq, Debug)]
         #[repr(C)]
-        struct Deep4(Deep3);
-        #[derive(Iffi, PartialEq, Debug, Clone, Copy)]
+        struct Header {
+            id: NonZeroU16,
+            #[iffi(bits = 0..4)]
+            flags: u16,
+        }
+
+        #[derive(Iffi, Clone, Copy, PartialEq, Debug)]
         #[repr(C)]
-        struct Deep5(Deep4);
-        #[derive(Iffi, PartialEq, Debug, Clone, Copy)]
+        struct Record {
+            header: Header,
+            len: NonZeroU32,
+        }
+
         #[repr(C)]
-        struct Deep6(Deep5);
-        #[derive(Iffi, PartialEq, Debug, Clone, Copy)]
+        struct RawRecord(u16, u16, u32);
+
+        let record = Record {
+            header: Header {
+                id: NonZeroU16::new(1).unwrap(),
+                flags: 0b1010,
+            },
+            len: NonZeroU32::new(8).unwrap(),
+        };
+        assert_eq!(try_from_all(into::<_, MaybeInvalid<_>>(record)), Ok(record));
+
+        // SAFETY: `RawRecord` has the same layout as `Record`.
+        let raw: MaybeInvalid<Record> = unsafe { core::mem::transmute(RawRecord(0, 0xff, 0)) };
+        assert!(Record::can_transmute(&raw).is_err());
+        let errors = try_from_all::<Record, _>(raw).unwrap_err();
+        let paths: alloc::vec::Vec<_> = errors.iter().map(|error| error.path.fields()).collect();
+        assert_eq!(
+            paths,
+            [&["header", "id"][..], &["header", "flags"], &["len"]]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn explain() {
+        #[derive(Iffi, Clone, Copy, PartialEq, Debug)]
+        #[iffi(deny_padding)]
         #[repr(C)]
-        struct Deep7(Deep6);
-        #[derive(Iffi, PartialEq, Debug, Clone, Copy)]
+        struct Header {
+            id: NonZeroU16,
+            #[iffi(bits = 0..4)]
+            flags: u16,
+            len: NonZeroU32,
+        }
+
         #[repr(C)]
-        struct Deep8(Deep7);
+        struct RawHeader(u16, u16, u32);
 
-        roundtrip!(Deep8(Deep7(Deep6(Deep5(Deep4(Deep3(Deep2(Deep1(
-            NonZeroU8::new(5).unwrap(),
-        )))))))));
+        // SAFETY: `RawHeader` has the same layout as `Header`.
+        let raw: MaybeInvalid<Header> = unsafe { core::mem::transmute(RawHeader(1, 0xff, 0)) };
+        let report = explain_invalid(&raw);
+        assert!(!report.is_valid());
+        assert!(report.errors.is_empty());
+        let fields: alloc::vec::Vec<_> = report
+            .fields
+            .iter()
+            .map(|field| (field.layout.name, field.layout.offset, &field.bytes[..]))
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                ("id", 0, &[1, 0][..]),
+                ("flags", 2, &[0xff, 0]),
+                ("len", 4, &[0, 0, 0, 0])
+            ]
+        );
+        let invalid: alloc::vec::Vec<_> = report
+            .invalid_fields()
+            .map(|field| field.layout.name)
+            .collect();
+        assert_eq!(invalid, ["flags", "len"]);
 
-        let invalid: MaybeInvalid<Deep8> = MaybeInvalid::zeroed();
-        let from: Result<Deep8, _> = try_from(invalid);
+        let valid = into::<_, MaybeInvalid<_>>(Header {
+            id: NonZeroU16::new(1).unwrap(),
+            flags: 0,
+            len: NonZeroU32::new(1).unwrap(),
+        });
+        assert!(explain_invalid(&valid).is_valid());
+    }
+
+    #[test]
+    fn bit_pattern_accessors() {
+        let bits = BitPattern::from_le(&0x0102u16);
+        assert_eq!(bits.as_bytes(), [2, 1]);
+        assert_eq!(bits.len(), 2);
+        assert_eq!(bits.iter_bits().filter(|&bit| bit).count(), 2);
+        assert!(bits.iter_bits().nth(1).unwrap());
+        assert_eq!(bits.to_u128(), Some(0x0102));
+        assert_eq!(BitPattern::from_le(&-1i8).to_u128(), Some(0xff));
+        assert_eq!(BitPattern::from_le(&[0u8; 17]).to_u128(), None);
+        assert!(BitPattern::from_le(&()).is_empty());
         assert_eq!(
-            from,
-            Err(Error {
-                error: ErrorKind::InvalidBitPattern {
-                    bits: BitPattern::from_le(&0u8),
-                    valid: BitRanges::from_le(&[1u8..=0xff])
-                },
-                from: type_name::<MaybeInvalid<NonZeroU8>>(),
-                into: type_name::<NonZeroU8>()
-            })
-        )
+            BitPattern::from_be(&[1u8, 2]),
+            BitPattern::from_le_bytes(&[2, 1])
+        );
+        assert_eq!(BitPattern::from_be(&0x0102u16.to_be()), bits);
+        assert_eq!(
+            BitRanges::from_be(&[[0u8, 1]..=[0, 2]]),
+            BitRanges::from_ne(&[1u16..=2])
+        );
+        #[cfg(not(feature = "alloc"))]
+        assert_eq!(
+            BitPattern::from_le(&[0u8; 64]).len(),
+            BitPattern::CAPACITY.min(64)
+        );
+    }
+
+    #[test]
+    fn bit_ranges() {
+        let ranges = BitRanges::from_ne_values(&[7u8, 2, 1, 3, 2], |value| value.checked_add(1));
+        assert_eq!(ranges, BitRanges::from_le(&[1u8..=3, 7..=7]));
+        assert_ne!(ranges, BitRanges::from_le(&[1u8..=3]));
+        assert!(ranges > BitRanges::from_le(&[1u8..=3]));
+        assert!(ranges < BitRanges::from_le(&[2u8..=2]));
+        assert!(BitPattern::from_le(&[1u8]) < BitPattern::from_le(&[2u8]));
+        assert_eq!(
+            BitRanges::from_ne::<u8>(&[]),
+            BitRanges::from_ne::<u32>(&[])
+        );
+        #[cfg(feature = "alloc")]
+        assert_eq!(alloc::format!("{ranges}"), "[0x01..=0x03, 0x07..=0x07]");
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn bit_pattern_formatting() {
+        use alloc::format;
+
+        let bits = BitPattern::from_le(&0x1fu16);
+        assert_eq!(format!("{bits}"), "0x001f");
+        assert_eq!(format!("{bits:>8}"), "  0x001f");
+        assert_eq!(format!("{bits:-<8}"), "0x001f--");
+        assert_eq!(format!("{bits:08}"), "0x00001f");
+        assert_eq!(format!("{bits:x}"), "001f");
+        assert_eq!(format!("{bits:#X}"), "0x001F");
+        assert_eq!(format!("{bits:b}"), "0000000000011111");
+        assert_eq!(format!("{bits:#020b}"), "0b000000000000011111");
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn custom_error() {
+        use alloc::{boxed::Box, string::ToString};
+
+        let error = ErrorKind::Custom(Box::new(core::fmt::Error));
+        assert_eq!(
+            error.to_string(),
+            "an error occurred when formatting an argument"
+        );
+        assert_eq!(error.clone().to_string(), error.to_string());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn hashed_errors() {
+        use std::collections::HashSet;
+
+        let errors: HashSet<Error> = [0u8, 0, 0]
+            .iter()
+            .map(|&raw| try_from::<NonZeroU8, u8>(raw).unwrap_err())
+            .chain([try_from::<NonZeroU16, u16>(0).unwrap_err()])
+            .collect();
+        assert_eq!(errors.len(), 2);
+    }
+
+    #[test]
+    fn out_parameters() {
+        extern "C" fn get_id(out: *mut u32) {
+            unsafe { out.write(7) };
+        }
+
+        let mut id = MaybeInvalid::<NonZeroU32>::zeroed();
+        get_id(id.as_mut_ptr() as *mut u32);
+        assert_eq!(id.as_uninit().as_ptr(), id.as_ptr());
+        assert_eq!(unsafe { id.into_inner_uninit().assume_init() }.get(), 7);
+
+        let raw = [0u32, 7];
+        assert_eq!(
+            unsafe { try_from_ptr::<NonZeroU32, _>(&raw[1]) },
+            Ok(NonZeroU32::new(7).unwrap())
+        );
+        assert!(unsafe { try_from_ptr::<NonZeroU32, _>(&raw[0]) }.is_err());
+        assert_eq!(
+            unsafe { try_from_ptr::<NonZeroU32, u32>(core::ptr::null()) }.map_err(|e| e.error),
+            Err(ErrorKind::NullPtr)
+        );
+        let misaligned = (raw.as_ptr() as *const u8).wrapping_add(2) as *const u32;
+        assert_eq!(
+            unsafe { try_from_ptr::<NonZeroU32, u32>(misaligned) }.map_err(|e| e.error),
+            Err(ErrorKind::Misaligned {
+                required: 4,
+                found: 2
+            })
+        );
+
+        let mut id = NonZeroU32::new(1).unwrap();
+        assert_eq!(*into_ref::<_, u32>(&id), 1);
+        get_id(unsafe { into_mut::<_, u32>(&mut id) });
+        assert_eq!(id.get(), 7);
+    }
+
+    #[test]
+    fn byte_buffers() {
+        #[derive(Iffi, Clone, Copy, PartialEq, Debug)]
+        #[repr(C)]
+        struct Header {
+            len: NonZeroU16,
+            kind: u16,
+        }
+
+        let mut buf = [0u8; 5];
+        buf[1..3].copy_from_slice(&4u16.to_ne_bytes());
+        // the header starts at an odd offset so is misaligned.
+        let header: Header = from_bytes(&buf[1..]).unwrap();
+        assert_eq!(header.len.get(), 4);
+        assert_eq!(
+            from_bytes::<Header>(&buf).map_err(|e| e.error),
+            Err(ErrorKind::SizeMismatch {
+                expected: 4,
+                found: 5
+            })
+        );
+        assert!(from_bytes::<Header>(&[0; 4]).is_err());
+
+        let (header, rest) = try_from_bytes_prefix::<Header>(&buf[1..]).unwrap();
+        assert_eq!(header.len.get(), 4);
+        assert!(rest.is_empty());
+        assert_eq!(
+            try_from_bytes_prefix::<Header>(&buf[2..]).map_err(|e| e.error),
+            Err(ErrorKind::SizeMismatch {
+                expected: 4,
+                found: 3
+            })
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn streams() {
+        let mut stream = std::vec::Vec::new();
+        write_ffi(&mut stream, &NonZeroU16::new(3).unwrap()).unwrap();
+        write_ffi(&mut stream, &0u16).unwrap();
+
+        let mut stream = &stream[..];
+        let value = read_ffi::<NonZeroU16, u16>(&mut stream).unwrap();
+        assert_eq!(value, Ok(NonZeroU16::new(3).unwrap()));
+        assert!(read_ffi::<NonZeroU16, u16>(&mut stream).unwrap().is_err());
+        assert!(read_ffi::<NonZeroU16, u16>(&mut stream).is_err());
+    }
+
+    #[test]
+    fn ffi_bytes() {
+        #[derive(Iffi, Clone, Copy, PartialEq, Debug)]
+        #[repr(C)]
+        #[iffi(deny_padding)]
+        struct Header {
+            len: NonZeroU16,
+            kind: u16,
+            data: [u8; 4],
+        }
+
+        let header = Header {
+            len: NonZeroU16::new(8).unwrap(),
+            kind: 1,
+            data: [1, 2, 3, 4],
+        };
+        let bytes: [u8; 8] = to_ffi_bytes(&header);
+        assert_eq!(as_ffi_bytes(&header), bytes);
+        assert_eq!(from_bytes::<Header>(&bytes), Ok(header));
+    }
+
+    #[test]
+    fn copied_invalid() {
+        let mut ids = [MaybeInvalid::<NonZeroU32>::zeroed(); 3];
+        ids[1] = MaybeInvalid::new(NonZeroU32::new(1).unwrap());
+        let copy = ids;
+        assert!(try_from_slice::<NonZeroU32, _>(&copy).is_err());
+        assert!(try_from::<NonZeroU32, _>(copy[1]).is_ok());
+        assert_eq!(copy, ids);
+        assert_eq!(copy[1].validate().map(|id| id.get()), Ok(1));
+        assert!(validate::<NonZeroU32, _>(&copy[0]).is_err());
+        assert_ne!(copy[0], copy[1]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn filled_buffers() {
+        let zeroed: alloc::vec::Vec<MaybeInvalid<NonZeroU32>> = alloc::vec![Default::default(); 4];
+        assert!(try_from_vec::<NonZeroU32, _>(zeroed).is_err());
+        let filled = alloc::vec![MaybeInvalid::<NonZeroU32>::filled(1); 4];
+        assert_eq!(
+            try_from_vec::<NonZeroU32, _>(filled).unwrap(),
+            [NonZeroU32::new(0x01010101).unwrap(); 4]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn boxed() {
+        use alloc::boxed::Box;
+
+        let nonzero = NonZeroU32::new(7).unwrap();
+        let raw: Box<u32> = into_boxed(Box::new(nonzero));
+        assert_eq!(*raw, 7);
+        assert_eq!(try_from_boxed(raw), Ok(Box::new(nonzero)));
+
+        let invalid: Result<Box<NonZeroU32>, _> = try_from_boxed(Box::new(0u32));
+        assert!(invalid.is_err());
+
+        let ptr = Box::into_raw(Box::new(5u64));
+        // SAFETY: the pointer came from `Box::into_raw`.
+        assert_eq!(
+            unsafe { try_option_box_from_ptr(ptr) },
+            Ok(Some(Box::new(5)))
+        );
+        // SAFETY: null is always accepted.
+        assert_eq!(
+            unsafe { try_option_box_from_ptr::<u64>(core::ptr::null_mut()) },
+            Ok(None)
+        );
+
+        #[derive(Iffi, Clone, Copy, PartialEq, Debug)]
+        #[iffi(deny_padding)]
+        #[repr(C)]
+        struct Block {
+            id: NonZeroU32,
+            data: [u8; 16 * 1024],
+        }
+
+        let mut raw = Box::new(MaybeInvalid::<Block>::zeroed());
+        assert!(try_from_ref_boxed::<Block, _>(&*raw).is_err());
+        raw.as_bytes_mut()[..4].copy_from_slice(&1u32.to_ne_bytes());
+        let block: Box<Block> = try_from_ref_boxed(&*raw).unwrap();
+        assert_eq!(block.id.get(), 1);
+        assert_eq!(try_from_ref::<NonZeroU32, _>(&7u32), Ok(nonzero));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn shared_and_slices() {
+        use alloc::{rc::Rc, sync::Arc, vec, vec::Vec};
+
+        let raw: Vec<u8> = vec![1, 2, 3];
+        let safe: Vec<NonZeroU8> = try_from_vec(raw).unwrap();
+        assert_eq!(into_vec::<_, u8>(safe), [1, 2, 3]);
+
+        let raw: Vec<u8> = vec![1, 0, 3];
+        assert!(try_from_boxed_slice::<NonZeroU8, u8>(raw.into_boxed_slice()).is_err());
+
+        let rc: Rc<NonZeroU8> = try_from_rc(Rc::new(4u8)).unwrap();
+        assert_eq!(*into_rc::<_, u8>(rc), 4);
+
+        let arc: Result<Arc<NonZeroU8>, _> = try_from_arc(Arc::new(0u8));
+        assert!(arc.is_err());
+    }
+
+    #[test]
+    fn slices() {
+        let raw = [1u16, 2, 3];
+        let safe: &[NonZeroU16] = try_from_slice(&raw).unwrap();
+        assert_eq!(safe[2].get(), 3);
+
+        let invalid = try_from_slice::<NonZeroU16, u16>(&[1, 0]).unwrap_err();
+        #[cfg(feature = "alloc")]
+        assert!(matches!(
+            invalid.error,
+            ErrorKind::InvalidElement { index: 1, .. }
+        ));
+        #[cfg(not(feature = "alloc"))]
+        assert!(matches!(invalid.error, ErrorKind::InvalidBitPattern { .. }));
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn parallel_slices() {
+        use alloc::vec::Vec;
+
+        let mut raw: Vec<u32> = (1..10_000).collect();
+        assert!(par_try_from_slice::<NonZeroU32, u32>(&raw).is_ok());
+
+        raw[5_000] = 0;
+        assert!(par_validate::<NonZeroU32, u32>(&raw).is_err());
+    }
+
+    #[test]
+    fn derive_iffi() {
+        #[derive(Iffi)]
+        #[repr(C)]
+        #[allow(dead_code)]
+        struct A {
+            a: u8,
+            b: NonZeroU8,
+            #[iffi(with = "u8")]
+            c: u8,
+            #[iffi(with = u16)]
+            d: NonZeroU16,
+            #[iffi(with = core::num::Wrapping<u32>)]
+            e: core::num::Wrapping<u32>,
+        }
+
+        #[derive(Iffi, Clone, Copy, PartialEq, Debug)]
+        #[repr(isize, align(1024))]
+        enum TA {
+            A,
+            B,
+            D(u32) = 150,
+            E { a: u16, b: NonZeroU8 },
+        }
+
+        roundtrip!(TA::A);
+        roundtrip!(TA::B);
+        roundtrip!(TA::D(5));
+        roundtrip!(TA::E {
+            a: 100,
+            b: NonZeroU8::new(3).unwrap(),
+        });
+    }
+
+    #[test]
+    fn c_enum() {
+        #[derive(Iffi, Clone, Copy, PartialEq, Debug)]
+        #[repr(C)]
+        enum Colour {
+            Red,
+            Green = 5,
+            Blue,
+        }
+
+        assert_eq!(
+            core::mem::size_of::<Colour>(),
+            core::mem::size_of::<core::ffi::c_int>()
+        );
+        roundtrip!(Colour::Red);
+        roundtrip!(Colour::Green);
+        roundtrip!(Colour::Blue);
+        let invalid: MaybeInvalid<Colour> = unsafe { core::mem::transmute(1 as core::ffi::c_int) };
+        assert_fails!(Colour = invalid => ErrorKind::InvalidEnumDiscriminant {
+            ty: type_name::<Colour>(),
+            bits: BitPattern::from_le(&(1 as core::ffi::c_int).to_le_bytes()),
+            valid: BitRanges::from_le::<core::ffi::c_int>(&[0..=0, 5..=6]),
+        });
+    }
+
+    #[test]
+    fn discriminants() {
+        #[derive(Iffi, Clone, Copy, PartialEq, Debug)]
+        #[repr(i8)]
+        enum Signed {
+            Min = -128,
+            AfterMin,
+            Neg = -1,
+            Zero,
+            High = 126,
+            Max,
+        }
+
+        #[derive(Iffi, Clone, Copy, PartialEq, Debug)]
+        #[repr(u8)]
+        enum Shifted {
+            A = 1 << 1,
+            B,
+            C = 4,
+            D = u8::MAX,
+        }
+
+        roundtrip!(Signed::Min);
+        roundtrip!(Signed::AfterMin);
+        roundtrip!(Signed::Neg);
+        roundtrip!(Signed::Zero);
+        roundtrip!(Signed::High);
+        roundtrip!(Signed::Max);
+        assert_eq!(Signed::Zero as i8, 0);
+        let invalid: MaybeInvalid<Signed> = unsafe { core::mem::transmute(1i8) };
+        assert_fails!(Signed = invalid => ErrorKind::InvalidEnumDiscriminant {
+            ty: type_name::<Signed>(),
+            bits: BitPattern::from_le(&1i8.to_le_bytes()),
+            valid: BitRanges::from_le::<i8>(&[-128..=-127, -1..=0, 126..=127]),
+        });
+
+        roundtrip!(Shifted::A);
+        roundtrip!(Shifted::B);
+        roundtrip!(Shifted::C);
+        roundtrip!(Shifted::D);
+        let invalid: MaybeInvalid<Shifted> = unsafe { core::mem::transmute(5u8) };
+        assert!(try_from::<Shifted, _>(invalid).is_err());
+    }
+
+    #[test]
+    fn tagged_enum() {
+        #[derive(Iffi, Clone, Copy, PartialEq, Debug)]
+        #[repr(C, u8)]
+        enum Tagged {
+            A(u8, NonZeroU32),
+            B { b: NonZeroU16 },
+            C,
+        }
+
+        #[derive(Iffi, Clone, Copy, PartialEq, Debug)]
+        #[repr(C)]
+        enum CTagged {
+            A(NonZeroU8),
+            B,
+        }
+
+        // the `(tag, union)` layout of `Tagged::A`.
+        #[repr(C)]
+        struct RawA {
+            tag: u8,
+            _pad: [u8; 3],
+            a: u8,
+            _pad2: [u8; 3],
+            b: u32,
+        }
+
+        let raw_a = |b| {
+            let raw = RawA {
+                tag: 0,
+                _pad: [0; 3],
+                a: 1,
+                _pad2: [0; 3],
+                b,
+            };
+            unsafe { core::mem::transmute::<_, MaybeInvalid<Tagged>>(raw) }
+        };
+
+        roundtrip!(Tagged::A(7, NonZeroU32::new(9).unwrap()));
+        roundtrip!(Tagged::B {
+            b: NonZeroU16::new(3).unwrap()
+        });
+        roundtrip!(Tagged::C);
+        roundtrip!(CTagged::A(NonZeroU8::new(1).unwrap()));
+        roundtrip!(CTagged::B);
+        assert!(try_from::<Tagged, _>(raw_a(0)).is_err());
+        assert_eq!(
+            try_from(raw_a(2)),
+            Ok(Tagged::A(1, NonZeroU32::new(2).unwrap()))
+        );
+        assert!(try_from::<CTagged, _>(MaybeInvalid::zeroed()).is_err());
+    }
+
+    #[test]
+    #[allow(dead_code)]
+    fn trivial_check() {
+        #[derive(Iffi)]
+        #[repr(C)]
+        struct Plain {
+            a: u8,
+            b: [u16; 2],
+        }
+
+        #[derive(Iffi)]
+        #[repr(C)]
+        struct Checked {
+            a: u8,
+            b: NonZeroU8,
+        }
+
+        #[derive(Iffi)]
+        #[repr(u8)]
+        enum Fieldless {
+            A,
+        }
+
+        const _: () = {
+            assert!(<Plain as Iffi>::TRIVIAL_CHECK);
+            assert!(!<Checked as Iffi>::TRIVIAL_CHECK);
+            assert!(!<Fieldless as Iffi>::TRIVIAL_CHECK);
+            assert!(<u32 as Iffi<u32>>::TRIVIAL_CHECK);
+        };
+    }
+
+    #[test]
+    fn custom_check() {
+        fn power_of_two(value: &u32) -> Result<(), ErrorKind> {
+            value
+                .is_power_of_two()
+                .then_some(())
+                .ok_or(ErrorKind::InvalidBitPattern {
+                    bits: BitPattern::from_le(value),
+                    valid: BitRanges::from_le::<u32>(&[]),
+                })
+        }
+
+        #[derive(Iffi, PartialEq, Debug)]
+        #[repr(C)]
+        struct A {
+            #[iffi(with = u32, check = power_of_two)]
+            a: u32,
+        }
+
+        roundtrip!(A { a: 64 });
+        let from: Result<A, _> = try_from(MaybeInvalid::new(A { a: 3 }));
+        assert_eq!(
+            from.unwrap_err(),
+            Error::new::<u32, u32>(ErrorKind::InvalidBitPattern {
+                bits: BitPattern::from_le(&3u32),
+                valid: BitRanges::from_le::<u32>(&[]),
+            })
+            .in_field::<A>("a")
+        );
+    }
+
+    #[test]
+    fn invariant() {
+        #[derive(Iffi, PartialEq, Debug)]
+        #[iffi(invariant = Buf::len_in_capacity)]
+        #[repr(C)]
+        struct Buf {
+            len: u32,
+            capacity: NonZeroU32,
+        }
+
+        impl Buf {
+            fn len_in_capacity(&self) -> Result<(), ErrorKind> {
+                (self.len <= self.capacity.get()).then_some(()).ok_or(
+                    ErrorKind::InvalidBitPattern {
+                        bits: BitPattern::from_le(&self.len),
+                        valid: BitRanges::from_le(&[0..=self.capacity.get()]),
+                    },
+                )
+            }
+        }
+
+        roundtrip!(Buf {
+            len: 2,
+            capacity: NonZeroU32::new(4).unwrap()
+        });
+        let from: Result<Buf, _> = try_from(MaybeInvalid::new(Buf {
+            len: 5,
+            capacity: NonZeroU32::new(4).unwrap(),
+        }));
+        assert_eq!(from.unwrap_err().into, type_name::<Buf>(),);
+    }
+
+    #[test]
+    fn assume_valid() {
+        #[derive(Iffi, PartialEq, Debug)]
+        #[repr(C)]
+        struct Handle {
+            // SAFETY: only ever converted from handles that were already validated.
+            #[iffi(unsafe(assume_valid))]
+            raw: NonZeroU32,
+        }
+
+        const _: () = assert!(<Handle as Iffi>::TRIVIAL_CHECK);
+
+        roundtrip!(Handle {
+            raw: NonZeroU32::new(12).unwrap()
+        });
+    }
+
+    #[test]
+    fn std_traits() {
+        #[derive(Iffi, PartialEq, Debug)]
+        #[iffi(impl_std_traits)]
+        #[repr(C)]
+        struct A {
+            a: NonZeroU8,
+        }
+
+        let a = A {
+            a: NonZeroU8::new(1).unwrap(),
+        };
+        let raw = MaybeInvalid::from(a);
+        assert_eq!(
+            A::try_from(raw),
+            Ok(A {
+                a: NonZeroU8::new(1).unwrap()
+            })
+        );
+        assert!(A::try_from(MaybeInvalid::zeroed()).is_err());
+    }
+
+    #[test]
+    fn crate_path() {
+        mod facade {
+            pub use crate as iffi_reexport;
+        }
+
+        #[derive(Iffi, PartialEq, Debug)]
+        #[iffi(crate = "facade::iffi_reexport")]
+        #[repr(C)]
+        struct A {
+            a: NonZeroU8,
+        }
+
+        #[derive(Nicheless)]
+        #[iffi(crate = "facade::iffi_reexport")]
+        #[repr(C)]
+        struct B {
+            b: u8,
+        }
+
+        roundtrip!(A {
+            a: NonZeroU8::new(9).unwrap()
+        });
+        assert_eq!(
+            try_from::<B, _>(MaybeInvalid::new(B { b: 3 })).unwrap().b,
+            3
+        );
+    }
+
+    #[test]
+    fn unions() {
+        #[derive(Iffi, Clone, Copy)]
+        #[repr(C)]
+        union Word {
+            int: u32,
+            float: f32,
+            bytes: [u8; 4],
+        }
+
+        #[derive(Nicheless, Clone, Copy)]
+        #[repr(C)]
+        union NichelessWord {
+            int: u32,
+            float: f32,
+        }
+
+        const _: () = assert!(<Word as Iffi>::TRIVIAL_CHECK);
+        let word: Word = try_from(into(Word { int: 5 })).unwrap();
+        assert_eq!(unsafe { word.int }, 5);
+        let word: NichelessWord = try_from(NichelessWord { float: 1.5 }).unwrap();
+        assert_eq!(unsafe { word.float }, 1.5);
+
+        // like bindgen generates, used directly in a universe.
+        #[derive(Nicheless, Clone, Copy)]
+        #[repr(C)]
+        union RawValue<T: Copy> {
+            int: T,
+            ptr: *const u8,
+        }
+
+        #[derive(Nicheless, Clone, Copy)]
+        #[repr(C)]
+        struct RawTagged {
+            tag: u8,
+            value: RawValue<u64>,
+        }
+
+        #[derive(Iffi, Clone, Copy)]
+        #[repr(C)]
+        #[iffi(universe = RawTagged)]
+        struct Tagged {
+            tag: NonZeroU8,
+            value: RawValue<u64>,
+        }
+
+        let raw = RawTagged {
+            tag: 1,
+            value: RawValue { int: 7 },
+        };
+        let tagged: Tagged = try_from(raw).unwrap();
+        assert_eq!(unsafe { tagged.value.int }, 7);
+    }
+
+    #[test]
+    fn tag_field() {
+        const KEY: u32 = 1;
+        const MOUSE: u32 = 2;
+
+        #[derive(Iffi, Clone, Copy, PartialEq, Debug)]
+        #[repr(C)]
+        struct Key {
+            code: NonZeroU16,
+        }
+
+        #[derive(Iffi, Clone, Copy, PartialEq, Debug)]
+        #[repr(C)]
+        struct Mouse {
+            x: i32,
+            y: i32,
+        }
+
+        #[derive(Clone, Copy)]
+        #[repr(C)]
+        union Payload {
+            key: Key,
+            mouse: Mouse,
+        }
+
+        #[derive(Iffi, Clone, Copy)]
+        #[repr(C)]
+        #[iffi(tag_field = kind)]
+        struct Event {
+            kind: u32,
+            #[iffi(payload(key = KEY, mouse = MOUSE))]
+            payload: Payload,
+        }
+
+        let mouse = Event {
+            kind: MOUSE,
+            payload: Payload {
+                mouse: Mouse { x: 0, y: 0 },
+            },
+        };
+        let key = Event {
+            kind: KEY,
+            payload: Payload {
+                key: Key {
+                    code: NonZeroU16::new(4).unwrap(),
+                },
+            },
+        };
+        assert!(try_from::<Event, _>(into(mouse)).is_ok());
+        assert!(try_from::<Event, _>(into(key)).is_ok());
+
+        // a zeroed mouse payload is an invalid key.
+        let invalid: MaybeInvalid<Event> =
+            unsafe { core::mem::transmute(Event { kind: KEY, ..mouse }) };
+        assert!(try_from::<Event, _>(invalid).is_err());
+        assert_fails!(Event = MaybeInvalid::zeroed() => ErrorKind::InvalidEnumDiscriminant {
+            ty: type_name::<Event>(),
+            bits: BitPattern::from_le(&0u32.to_le_bytes()),
+            valid: BitRanges::from_le::<u32>(&[1..=2]),
+        });
+    }
+
+    #[test]
+    fn bits() {
+        #[derive(Iffi, Clone, Copy, PartialEq, Debug)]
+        #[repr(C)]
+        struct Flags {
+            #[iffi(bits = 0..=4)]
+            low: u8,
+            #[iffi(bits = "8..16")]
+            high: u32,
+            #[iffi(bits = 0, reserved_bits = ignore)]
+            ignored: u8,
+        }
+
+        roundtrip!(Flags {
+            low: 0b1_1111,
+            high: 0xff00,
+            ignored: 0xff,
+        });
+        let invalid = Flags {
+            low: 0b10_0000,
+            high: 0,
+            ignored: 0,
+        };
+        assert_fails!(Flags = into(invalid) => ErrorKind::ReservedBits {
+            bits: BitPattern::from_le(&0b10_0000u8),
+            mask: BitPattern::from_le(&0b1_1111u8),
+        });
+        let invalid = Flags {
+            low: 0,
+            high: 0x1_0000,
+            ignored: 0,
+        };
+        assert!(try_from::<Flags, _>(into(invalid)).is_err());
+    }
+
+    #[test]
+    fn ptr_len() {
+        #[derive(Iffi, Clone, Copy, PartialEq, Debug)]
+        #[repr(C)]
+        struct Buffer {
+            #[iffi(len = len)]
+            ptr: *const u32,
+            len: usize,
+        }
+
+        let data = [1u32, 2, 3];
+        roundtrip!(Buffer {
+            ptr: data.as_ptr(),
+            len: data.len(),
+        });
+        roundtrip!(Buffer {
+            ptr: core::ptr::null(),
+            len: 0,
+        });
+        assert_fails!(Buffer = into(Buffer { ptr: core::ptr::null(), len: 3 }) => ErrorKind::NullPtr);
+        let misaligned = Buffer {
+            ptr: (data.as_ptr() as *const u8).wrapping_add(1) as *const u32,
+            len: 1,
+        };
+        assert_fails!(Buffer = into(misaligned) => ErrorKind::Misaligned { required: 4, found: 1 });
+
+        #[derive(Iffi, Clone, Copy, PartialEq, Debug)]
+        #[repr(C)]
+        struct BufferMut {
+            len: u8,
+            #[iffi(len = len)]
+            ptr: *mut u16,
+        }
+
+        let misaligned = BufferMut {
+            len: 1,
+            ptr: 0x1001 as *mut u16,
+        };
+        assert_fails!(BufferMut = into(misaligned) => ErrorKind::Misaligned { required: 2, found: 1 });
+
+        #[derive(Iffi, Clone, Copy, PartialEq, Debug)]
+        #[repr(C, packed)]
+        struct Packed {
+            tag: u8,
+            len: u32,
+            #[iffi(len = len)]
+            ptr: *const u8,
+        }
+
+        roundtrip!(Packed {
+            tag: 0,
+            len: 0,
+            ptr: core::ptr::null(),
+        });
+        let null = Packed {
+            tag: 0,
+            len: 2,
+            ptr: core::ptr::null(),
+        };
+        assert_fails!(Packed = into(null) => ErrorKind::NullPtr);
+
+        #[derive(Iffi, Clone, Copy, PartialEq, Debug)]
+        #[repr(u8)]
+        enum Slice {
+            Empty,
+            Words {
+                len: u16,
+                #[iffi(len = len)]
+                ptr: *const u16,
+            },
+        }
+
+        roundtrip!(Slice::Empty);
+        roundtrip!(Slice::Words {
+            len: 0,
+            ptr: core::ptr::null(),
+        });
+        let null = Slice::Words {
+            len: 1,
+            ptr: core::ptr::null(),
+        };
+        assert_fails!(Slice = into(null) => ErrorKind::NullPtr);
+    }
+
+    #[test]
+    fn mirror() {
+        #[iffi::mirror(RawPoint)]
+        #[derive(Clone, Copy, PartialEq, Debug)]
+        #[repr(C)]
+        struct Point {
+            x: NonZeroU32,
+            #[iffi(with = u32)]
+            y: NonZeroU32,
+        }
+
+        #[iffi::mirror(RawPair)]
+        #[derive(Iffi, Clone, Copy, PartialEq, Debug)]
+        #[repr(C)]
+        struct Pair(u8, #[iffi(with = u8)] NonZeroU8);
+
+        let point = Point {
+            x: NonZeroU32::new(1).unwrap(),
+            y: NonZeroU32::new(2).unwrap(),
+        };
+        let raw = RawPoint::from(point);
+        assert_eq!(raw.y, 2);
+        assert_eq!(Point::try_from(raw), Ok(point));
+        let raw = RawPoint {
+            x: MaybeInvalid::new(NonZeroU32::new(1).unwrap()),
+            y: 0,
+        };
+        assert!(Point::try_from(raw).is_err());
+
+        let pair = Pair(0, NonZeroU8::new(1).unwrap());
+        assert_eq!(Pair::try_from(RawPair(MaybeInvalid::new(0), 1)), Ok(pair));
+        let universe: MaybeInvalid<Pair> = into(pair);
+        assert_eq!(try_from(universe), Ok(pair));
+
+        #[iffi::mirror(RawSpan)]
+        #[derive(Clone, Copy, PartialEq, Debug)]
+        #[iffi(invariant = Span::ordered, deny_padding)]
+        #[repr(C)]
+        struct Span {
+            start: u32,
+            end: u32,
+        }
+
+        impl Span {
+            fn ordered(&self) -> Result<(), ErrorKind> {
+                (self.start <= self.end)
+                    .then_some(())
+                    .ok_or(ErrorKind::InvalidBitPattern {
+                        bits: BitPattern::from_ne(&self.end),
+                        valid: BitRanges::from_ne(&[self.start..=u32::MAX]),
+                    })
+            }
+        }
+
+        fn no_padding<T: NoPadding>() {}
+        no_padding::<Span>();
+        let span = Span { start: 1, end: 2 };
+        assert_eq!(Span::try_from(RawSpan::from(span)), Ok(span));
+        assert!(Span::try_from(RawSpan {
+            start: MaybeInvalid::new(2),
+            end: MaybeInvalid::new(1),
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn universe() {
+        #[derive(Nicheless, Clone, Copy)]
+        #[repr(C)]
+        struct RawPoint {
+            x: u32,
+            y: u32,
+            tag: u8,
+        }
+
+        #[derive(Iffi, Clone, Copy, PartialEq, Debug)]
+        #[repr(C)]
+        #[iffi(universe = RawPoint, impl_std_traits)]
+        struct Point {
+            x: NonZeroU32,
+            y: u32,
+            #[iffi(bits = 0..=1)]
+            tag: u8,
+        }
+
+        let point = Point {
+            x: NonZeroU32::new(4).unwrap(),
+            y: 0,
+            tag: 1,
+        };
+        let raw = RawPoint::from(point);
+        assert_eq!((raw.x, raw.y, raw.tag), (4, 0, 1));
+        assert_eq!(Point::try_from(raw), Ok(point));
+        let raw = RawPoint { x: 0, y: 0, tag: 0 };
+        assert!(Point::try_from(raw).is_err());
+        let raw = RawPoint { x: 1, y: 0, tag: 4 };
+        assert!(Point::try_from(raw).is_err());
+    }
+
+    #[test]
+    fn one_niche() {
+        #[derive(OneNiche, Iffi, Clone, Copy, PartialEq, Debug)]
+        #[repr(transparent)]
+        #[one_niche(value = 0)]
+        struct Handle(NonZeroU32);
+
+        #[derive(OneNiche)]
+        #[repr(transparent)]
+        #[one_niche(value = 0)]
+        struct Ptr<T>(core::ptr::NonNull<T>);
+
+        fn nicheless<T: Nicheless>() {}
+        nicheless::<Option<Handle>>();
+        nicheless::<Option<Ptr<u8>>>();
+        nicheless::<Option<extern "system" fn(u8, u16) -> u32>>();
+        nicheless::<Option<unsafe extern "C-unwind" fn(*const u8)>>();
+
+        #[cfg(all(feature = "std", unix))]
+        {
+            use std::os::fd::{AsRawFd, RawFd};
+
+            let stdin = std::io::stdin();
+            let fd: ValidFd = try_from(stdin.as_raw_fd()).unwrap();
+            assert_eq!(fd.as_raw_fd(), 0);
+            assert!(try_from::<ValidFd, RawFd>(-1).is_err());
+        }
+
+        let handle = Some(Handle(NonZeroU32::new(3).unwrap()));
+        assert_eq!(try_from(handle), Ok(handle));
+
+        #[derive(Iffi, Clone, Copy, PartialEq, Debug)]
+        #[iffi(crate = "crate")]
+        #[repr(C)]
+        struct Window {
+            parent: Option<Handle>,
+            id: Handle,
+        }
+
+        roundtrip!(Window {
+            parent: None,
+            id: Handle(NonZeroU32::MIN),
+        });
+        let raw: MaybeInvalid<Window> = unsafe { core::mem::transmute([0u32, 0]) };
+        assert!(try_from::<Window, _>(raw).is_err());
+        let raw: MaybeInvalid<Window> = unsafe { core::mem::transmute([0u32, 2]) };
+        assert_eq!(try_from::<Window, _>(raw).map(|w| w.parent), Ok(None));
+    }
+
+    #[test]
+    fn assertions() {
+        #[derive(Iffi, Clone, Copy)]
+        #[repr(C)]
+        struct Point {
+            x: NonZeroU32,
+            y: u16,
+        }
+
+        assert_iffi!(Point);
+        assert_iffi!(NonZeroU8, u8);
+        assert_nicheless!(MaybeInvalid<Point>, [u16; 3]);
+    }
+
+    #[test]
+    fn unsafe_impl_nicheless() {
+        #[repr(C)]
+        struct Header {
+            len: u32,
+            data: *const u8,
+        }
+
+        #[repr(transparent)]
+        struct Handle(NonZeroU16);
+
+        unsafe_impl_nicheless! {
+            unsafe impl Nicheless for Header => "SAFETY: a `#[repr(C)]` struct of nicheless fields";
+            unsafe impl OneNiche for Handle => "SAFETY: transparent over a one-niche type";
+        }
+
+        assert_nicheless!(Header, Option<Handle>);
+        let header: MaybeInvalid<Header> = MaybeInvalid::zeroed();
+        let header: Header = try_from(header).unwrap();
+        assert_eq!((header.len, header.data), (0, core::ptr::null()));
+    }
+
+    #[test]
+    fn nicheless_generics() {
+        #[derive(Nicheless, Clone, Copy, PartialEq, Debug)]
+        #[repr(C)]
+        struct Buf<T: Copy, const N: usize>([T; N], u8);
+
+        fn nicheless<T: Nicheless>() {}
+        nicheless::<Buf<u32, 4>>();
+        let buf = Buf([1u16, 2], 3);
+        assert_eq!(try_from(MaybeInvalid::new(buf)), Ok(buf));
+    }
+
+    #[test]
+    fn nicheless_enum() {
+        #[derive(Nicheless, Clone, Copy, PartialEq, Debug)]
+        #[repr(u8)]
+        #[rustfmt::skip]
+        #[allow(dead_code)]
+        enum Byte {
+            V0, V1, V2, V3, V4, V5, V6, V7, V8, V9, V10, V11, V12, V13, V14, V15,
+            V16, V17, V18, V19, V20, V21, V22, V23, V24, V25, V26, V27, V28, V29, V30, V31,
+            V32, V33, V34, V35, V36, V37, V38, V39, V40, V41, V42, V43, V44, V45, V46, V47,
+            V48, V49, V50, V51, V52, V53, V54, V55, V56, V57, V58, V59, V60, V61, V62, V63,
+            V64, V65, V66, V67, V68, V69, V70, V71, V72, V73, V74, V75, V76, V77, V78, V79,
+            V80, V81, V82, V83, V84, V85, V86, V87, V88, V89, V90, V91, V92, V93, V94, V95,
+            V96, V97, V98, V99, V100, V101, V102, V103, V104, V105, V106, V107, V108, V109, V110, V111,
+            V112, V113, V114, V115, V116, V117, V118, V119, V120, V121, V122, V123, V124, V125, V126, V127,
+            V128, V129, V130, V131, V132, V133, V134, V135, V136, V137, V138, V139, V140, V141, V142, V143,
+            V144, V145, V146, V147, V148, V149, V150, V151, V152, V153, V154, V155, V156, V157, V158, V159,
+            V160, V161, V162, V163, V164, V165, V166, V167, V168, V169, V170, V171, V172, V173, V174, V175,
+            V176, V177, V178, V179, V180, V181, V182, V183, V184, V185, V186, V187, V188, V189, V190, V191,
+            V192, V193, V194, V195, V196, V197, V198, V199, V200, V201, V202, V203, V204, V205, V206, V207,
+            V208, V209, V210, V211, V212, V213, V214, V215, V216, V217, V218, V219, V220, V221, V222, V223,
+            V224, V225, V226, V227, V228, V229, V230, V231, V232, V233, V234, V235, V236, V237, V238, V239,
+            V240, V241, V242, V243, V244, V245, V246, V247, V248, V249, V250, V251, V252, V253, V254, V255,
+        }
+
+        fn nicheless<T: Nicheless>() {}
+        nicheless::<Byte>();
+        let byte: Byte = try_from(MaybeInvalid::<Byte>::zeroed()).unwrap();
+        assert_eq!(byte, Byte::V0);
+    }
+
+    #[test]
+    fn packed() {
+        #[derive(Iffi, PartialEq, Debug, Clone, Copy)]
+        #[repr(C, packed)]
+        struct Wire {
+            kind: u8,
+            len: NonZeroU32,
+            #[iffi(with = u16)]
+            port: NonZeroU16,
+        }
+
+        #[derive(Iffi, PartialEq, Debug, Clone, Copy)]
+        #[repr(C, packed(2))]
+        struct Wire2 {
+            kind: u8,
+            len: NonZeroU32,
+        }
+
+        roundtrip!(Wire {
+            kind: 1,
+            len: NonZeroU32::new(300).unwrap(),
+            port: NonZeroU16::new(80).unwrap(),
+        });
+        roundtrip!(Wire2 {
+            kind: 1,
+            len: NonZeroU32::new(300).unwrap(),
+        });
+        assert!(try_from::<Wire, _>(MaybeInvalid::zeroed()).is_err());
+    }
+
+    #[test]
+    fn derive_generics() {
+        #[derive(Iffi)]
+        #[repr(C)]
+        #[allow(dead_code)]
+        struct A<T: Iffi, U> {
+            b: T,
+            a: PhantomData<U>,
+        }
+
+        #[derive(Iffi, PartialEq, Debug)]
+        #[repr(C)]
+        struct B<T> {
+            b: T,
+        }
+
+        #[derive(Iffi, PartialEq, Debug)]
+        #[repr(u8)]
+        enum C<T, U> {
+            A(T),
+            B { u: U },
+        }
+
+        #[derive(Iffi, PartialEq, Debug)]
+        #[iffi(bound = "T: Iffi<U>, U: Nicheless")]
+        #[repr(C)]
+        struct D<T, U> {
+            #[iffi(with = "U")]
+            d: T,
+            u: PhantomData<U>,
+        }
+
+        roundtrip!(B {
+            b: NonZeroU8::new(1).unwrap()
+        });
+        roundtrip!(D {
+            d: NonZeroU8::new(1).unwrap(),
+            u: PhantomData::<u8>,
+        });
+        roundtrip!(C::<NonZeroU8, u16>::A(NonZeroU8::new(2).unwrap()));
+        roundtrip!(C::<NonZeroU8, u16>::B { u: 3 });
+        assert!(try_from::<B<NonZeroU8>, _>(MaybeInvalid::zeroed()).is_err());
+    }
+
+    #[test]
+    fn nested() {
+        #[derive(Iffi, PartialEq, Debug)]
+        #[repr(C)]
+        struct A;
+
+        #[derive(Iffi, PartialEq, Debug)]
+        #[repr(C)]
+        struct B(A);
+
+        #[derive(Iffi, PartialEq, Debug)]
+        #[repr(C)]
+        struct C {
+            b: B,
+        }
+
+        #[derive(Iffi, PartialEq, Debug)]
+        #[repr(u8)]
+        #[allow(clippy::enum_variant_names)]
+        enum D {
+            A(A),
+            B(B),
+            C(C),
+            D { c: C },
+            E,
+        }
+
+        roundtrip!(A);
+        roundtrip!(B(A));
+        roundtrip!(C { b: B(A) });
+        roundtrip!(D::A(A));
+        roundtrip!(D::B(B(A)));
+        roundtrip!(D::C(C { b: B(A) }));
+        roundtrip!(D::D { c: C { b: B(A) } });
+        roundtrip!(D::E);
+
+        #[derive(Iffi, PartialEq, Debug, Clone, Copy)]
+        #[repr(C)]
+        struct Deep1(NonZeroU8);
+        #[derive(Iffi, PartialEq, Debug, Clone, Copy)]
+        #[repr(C)]
+        struct Deep2(Deep1);
+        #[derive(Iffi, PartialEq, Debug, Clone, Copy)]
+        #[repr(C)]
+        struct Deep3(Deep2);
+        #[derive(Iffi, PartialEq, Debug, Clone, Copy)]
+        #[repr(C)]
+        struct Deep4(Deep3);
+        #[derive(Iffi, PartialEq, Debug, Clone, Copy)]
+        #[repr(C)]
+        struct Deep5(Deep4);
+        #[derive(Iffi, PartialEq, Debug, Clone, Copy)]
+        #[repr(C)]
+        struct Deep6(Deep5);
+        #[derive(Iffi, PartialEq, Debug, Clone, Copy)]
+        #[repr(C)]
+        struct Deep7(Deep6);
+        #[derive(Iffi, PartialEq, Debug, Clone, Copy)]
+        #[repr(C)]
+        struct Deep8(Deep7);
+
+        roundtrip!(Deep8(Deep7(Deep6(Deep5(Deep4(Deep3(Deep2(Deep1(
+            NonZeroU8::new(5).unwrap(),
+        )))))))));
+
+        let invalid: MaybeInvalid<Deep8> = MaybeInvalid::zeroed();
+        let from: Result<Deep8, _> = try_from(invalid);
+        let error = from.unwrap_err();
+        assert_eq!(
+            error.error,
+            ErrorKind::InvalidBitPattern {
+                bits: BitPattern::from_le(&0u8),
+                valid: BitRanges::from_le(&[1u8..=0xff])
+            }
+        );
+        assert_eq!(
+            (error.from, error.into),
+            (
+                type_name::<MaybeInvalid<NonZeroU8>>(),
+                type_name::<NonZeroU8>()
+            )
+        );
+        #[cfg(feature = "alloc")]
+        assert_eq!(error.path.fields(), ["0"; 8]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn field_paths() {
+        use alloc::string::ToString;
+
+        #[derive(Iffi, Clone, Copy, Debug)]
+        #[repr(C)]
+        struct Header {
+            len: u16,
+            flags: NonZeroU16,
+        }
+
+        #[derive(Iffi, Clone, Copy, Debug)]
+        #[repr(u8)]
+        #[allow(dead_code)]
+        enum Packet {
+            Empty,
+            Data { header: Header, seq: NonZeroU32 },
+        }
+
+        #[repr(C)]
+        struct RawData(u8, [u16; 2], u32);
+
+        let raw = RawData(1, [4, 0], 1);
+        // SAFETY: `RawData` has the same layout as the `Data` variant.
+        let raw: MaybeInvalid<Packet> = unsafe { core::mem::transmute(raw) };
+        let error = try_from::<Packet, _>(raw).unwrap_err();
+        assert_eq!(error.path.ty(), Some(type_name::<Packet>()));
+        assert_eq!(error.path.fields(), ["Data.header", "flags"]);
+        assert!(error.to_string().contains("Packet.Data.header.flags;"));
     }
 }
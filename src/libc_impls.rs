@@ -0,0 +1,32 @@
+use libc::*;
+
+use crate::Nicheless;
+
+macro_rules! impl_nicheless_bulk {
+    ($($ty:ty),+$(,)?) => {
+        $(
+            unsafe impl Nicheless for $ty {}
+        )+
+    };
+}
+
+// SAFETY: these are `#[repr(C)]` structs of integers, arrays and raw pointers,
+// including their private padding fields, so are valid for any bit-pattern.
+impl_nicheless_bulk!(
+    timespec,
+    timeval,
+    iovec,
+    pollfd,
+    tm,
+    rlimit,
+    stat,
+    sockaddr,
+    sockaddr_storage,
+    sockaddr_in,
+    sockaddr_in6,
+    sockaddr_un,
+    in_addr,
+    in6_addr,
+    linger,
+    msghdr,
+);
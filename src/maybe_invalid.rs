@@ -1,6 +1,6 @@
 use core::{fmt, mem::MaybeUninit};
 
-use crate::Nicheless;
+use crate::{Error, Iffi, Nicheless, NoPadding};
 
 /// Represents a value of the type `T` that may not be well-defined.
 ///
@@ -25,6 +25,21 @@ impl<T> MaybeInvalid<T> {
         Self(MaybeUninit::zeroed())
     }
 
+    /// Creates a potentially invalid value with every byte set to `byte`.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use iffi::MaybeInvalid;
+    /// let value = MaybeInvalid::<u32>::filled(0xff);
+    /// assert_eq!(value.into_inner(), u32::MAX);
+    /// ```
+    pub fn filled(byte: u8) -> Self {
+        let mut value = MaybeUninit::uninit();
+        // SAFETY: writing a byte to every byte of the value initializes it.
+        unsafe { core::ptr::write_bytes(value.as_mut_ptr(), byte, 1) };
+        Self(value)
+    }
+
     /// Gets a shared reference to the value in this container as a byte slice.
     pub fn as_bytes(&self) -> &[u8] {
         // SAEFTY: u8 has an alignment of 1 so is never unaligned
@@ -33,6 +48,58 @@ impl<T> MaybeInvalid<T> {
         }
     }
 
+    /// Gets a mutable (unique) reference to the value in this container as a byte slice.
+    ///
+    /// Any bytes may be written, since the value is not required to be valid,
+    /// so out-parameters can be filled incrementally before being validated.
+    /// Only implemented for types with [no padding], since padding bytes cannot be read.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use core::num::NonZeroU16;
+    /// # use iffi::MaybeInvalid;
+    /// let mut value = MaybeInvalid::<NonZeroU16>::zeroed();
+    /// value.as_bytes_mut().copy_from_slice(&7u16.to_ne_bytes());
+    /// assert_eq!(iffi::try_from::<NonZeroU16, _>(value).unwrap().get(), 7);
+    /// ```
+    ///
+    /// [no padding]: NoPadding
+    pub fn as_bytes_mut(&mut self) -> &mut [u8]
+    where
+        T: NoPadding,
+    {
+        // SAFETY: the type has no padding, so every byte of the value is initialized.
+        unsafe { self.as_bytes_mut_unchecked() }
+    }
+
+    /// Like [`as_bytes_mut`], but for any type.
+    ///
+    /// # Safety
+    ///
+    /// Every byte of the value, padding included, must be initialized,
+    /// as it is for values created with [`zeroed`] or [`filled`].
+    ///
+    /// [`as_bytes_mut`]: MaybeInvalid::as_bytes_mut
+    /// [`zeroed`]: MaybeInvalid::zeroed
+    /// [`filled`]: MaybeInvalid::filled
+    pub(crate) unsafe fn as_bytes_mut_unchecked(&mut self) -> &mut [u8] {
+        // SAFETY: u8 has an alignment of 1 so is never unaligned,
+        // and every byte is initialized by the caller so any bytes written keep it initialized.
+        unsafe {
+            core::slice::from_raw_parts_mut(
+                self.0.as_mut_ptr() as *mut u8,
+                core::mem::size_of::<Self>(),
+            )
+        }
+    }
+
+    /// Overwrites the value in this container with a valid one.
+    ///
+    /// The previous value is not dropped, since it may not be valid.
+    pub fn write(&mut self, value: T) {
+        self.0.write(value);
+    }
+
     /// Extracts the value from this container.
     ///
     /// # Safety
@@ -76,6 +143,34 @@ impl<T> MaybeInvalid<T> {
     pub fn as_ptr(&self) -> *const T {
         self.0.as_ptr()
     }
+
+    /// Gets a mutable pointer to the contained value,
+    /// such as for a C function to write through.
+    ///
+    /// Anything written through the pointer must leave the value initialized.
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        self.0.as_mut_ptr()
+    }
+
+    /// Gets a shared reference to the value in this container as a [`MaybeUninit`].
+    pub fn as_uninit(&self) -> &MaybeUninit<T> {
+        &self.0
+    }
+
+    /// Gets a mutable (unique) reference to the value in this container as a [`MaybeUninit`].
+    ///
+    /// # Safety
+    ///
+    /// The value must still be initialized when the reference is dropped,
+    /// for example it must not be overwritten with [`MaybeUninit::uninit`].
+    pub unsafe fn as_uninit_mut(&mut self) -> &mut MaybeUninit<T> {
+        &mut self.0
+    }
+
+    /// Extracts the value from this container as a [`MaybeUninit`].
+    pub fn into_inner_uninit(self) -> MaybeUninit<T> {
+        self.0
+    }
 }
 
 // SAFETY: MaybeInvalid does not require a well-defined contained value.
@@ -126,6 +221,47 @@ impl<T: Nicheless> MaybeInvalid<T> {
     }
 }
 
+impl<T: Iffi> MaybeInvalid<T> {
+    /// Checks the value in this container,
+    /// returning a shared reference to it if it is valid.
+    ///
+    /// See [`iffi::validate`].
+    ///
+    /// [`iffi::validate`]: crate::validate
+    pub fn validate(&self) -> Result<&T, Error> {
+        crate::validate(self)
+    }
+}
+
+/// Creates a zeroed value, as with [`MaybeInvalid::zeroed`].
+impl<T> Default for MaybeInvalid<T> {
+    fn default() -> Self {
+        Self::zeroed()
+    }
+}
+
+/// Copies the bytes of the value, which need not be valid.
+impl<T: Copy> Clone for MaybeInvalid<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: Copy> Copy for MaybeInvalid<T> {}
+
+/// Compares the raw bytes of the values, which need not be valid.
+///
+/// Only implemented for types with [no padding], since padding bytes cannot be read.
+///
+/// [no padding]: NoPadding
+impl<T: NoPadding> PartialEq for MaybeInvalid<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_bytes() == other.as_bytes()
+    }
+}
+
+impl<T: NoPadding> Eq for MaybeInvalid<T> {}
+
 impl<T> fmt::Debug for MaybeInvalid<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_tuple("MaybeInvalid")
@@ -0,0 +1,42 @@
+use mint::*;
+
+use crate::Nicheless;
+
+macro_rules! impl_nicheless_generic {
+    ($($ty:ident),+$(,)?) => {
+        $(
+            unsafe impl<T: Nicheless> Nicheless for $ty<T> {}
+        )+
+    };
+}
+
+// SAFETY: these are `#[repr(C)]` structs of nicheless fields.
+impl_nicheless_generic!(
+    Vector2,
+    Vector3,
+    Vector4,
+    Point2,
+    Point3,
+    Quaternion,
+    RowMatrix2,
+    RowMatrix2x3,
+    RowMatrix2x4,
+    RowMatrix3x2,
+    RowMatrix3,
+    RowMatrix3x4,
+    RowMatrix4x2,
+    RowMatrix4x3,
+    RowMatrix4,
+    ColumnMatrix2,
+    ColumnMatrix2x3,
+    ColumnMatrix2x4,
+    ColumnMatrix3x2,
+    ColumnMatrix3,
+    ColumnMatrix3x4,
+    ColumnMatrix4x2,
+    ColumnMatrix4x3,
+    ColumnMatrix4,
+);
+
+// SAFETY: as above, with a marker for the order of the angles.
+unsafe impl<T: Nicheless, B> Nicheless for EulerAngles<T, B> {}
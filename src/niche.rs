@@ -1,9 +1,10 @@
 use core::{
+    cell::{Cell, UnsafeCell},
     marker::{PhantomData, PhantomPinned},
-    mem::MaybeUninit,
+    mem::{ManuallyDrop, MaybeUninit},
     num::{
         NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroU128, NonZeroU16,
-        NonZeroU32, NonZeroU64, NonZeroU8, Wrapping,
+        NonZeroU32, NonZeroU64, NonZeroU8, Saturating, Wrapping,
     },
     ptr::NonNull,
 };
@@ -47,6 +48,7 @@ unsafe impl<T> Nicheless for MaybeUninit<T> {}
 // SAFETY: all types below will always be trivially nicheless.
 unsafe impl<T> Nicheless for PhantomData<T> {}
 unsafe impl Nicheless for PhantomPinned {}
+unsafe impl Nicheless for () {}
 unsafe impl Nicheless for u8 {}
 unsafe impl Nicheless for u16 {}
 unsafe impl Nicheless for u32 {}
@@ -69,6 +71,13 @@ unsafe impl<T> Nicheless for *mut T {}
 // SAFETY: made up of nicheless types.
 unsafe impl<T: Nicheless, const N: usize> Nicheless for [T; N] {}
 unsafe impl<T: Nicheless> Nicheless for Wrapping<T> {}
+unsafe impl<T: Nicheless> Nicheless for Saturating<T> {}
+unsafe impl<T: Nicheless> Nicheless for ManuallyDrop<T> {}
+
+// SAFETY: `#[repr(transparent)]` over `T`.
+// `SyncUnsafeCell` is left out as it is unstable.
+unsafe impl<T: Nicheless> Nicheless for UnsafeCell<T> {}
+unsafe impl<T: Nicheless> Nicheless for Cell<T> {}
 
 #[cfg(feature = "atomics")]
 mod atomics {
@@ -90,10 +99,81 @@ mod atomics {
     unsafe impl<T> Nicheless for AtomicPtr<T> {}
 }
 
+#[cfg(feature = "arch-simd")]
+mod arch_simd {
+    use crate::Nicheless;
+
+    macro_rules! impl_nicheless_bulk {
+        ($($ty:ident),+$(,)?) => {
+            $(
+                unsafe impl Nicheless for $ty {}
+            )+
+        };
+    }
+
+    // SAFETY: vectors are plain bits, valid for any bit-pattern.
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    const _: () = {
+        #[cfg(target_arch = "x86")]
+        use core::arch::x86::*;
+        #[cfg(target_arch = "x86_64")]
+        use core::arch::x86_64::*;
+
+        impl_nicheless_bulk!(__m128, __m128d, __m128i, __m256, __m256d, __m256i);
+    };
+
+    // SAFETY: as above.
+    #[cfg(target_arch = "aarch64")]
+    const _: () = {
+        use core::arch::aarch64::*;
+
+        impl_nicheless_bulk!(
+            int8x8_t,
+            int8x16_t,
+            int16x4_t,
+            int16x8_t,
+            int32x2_t,
+            int32x4_t,
+            int64x1_t,
+            int64x2_t,
+            uint8x8_t,
+            uint8x16_t,
+            uint16x4_t,
+            uint16x8_t,
+            uint32x2_t,
+            uint32x4_t,
+            uint64x1_t,
+            uint64x2_t,
+            float32x2_t,
+            float32x4_t,
+            float64x1_t,
+            float64x2_t,
+            poly8x8_t,
+            poly8x16_t,
+            poly16x4_t,
+            poly16x8_t,
+            poly64x1_t,
+            poly64x2_t,
+        );
+    };
+}
+
 /// Indicates that the type has exactly one niche, which is filled by [niche optimization]
 /// and so implements [`Nicheless`] in option-like enums (`Option<T>`, `Result<T, ()>`).
 ///
+/// Since an option-like enum of a `OneNiche` type is nicheless, it needs no validation:
+/// it implements [`Iffi`] over itself and over [`MaybeInvalid<Self>`] like any other nicheless type,
+/// so optional handles can be fields of derived structs as they are.
+/// Types with more than one niche cannot implement `OneNiche`;
+/// an `Option` of them cannot be validated, as the bit-pattern `None` takes is unspecified.
+///
+/// Derived or newtype handles can implement `OneNiche` with `#[derive(OneNiche)]`
+/// or [`unsafe_impl_nicheless!`].
+///
 /// [niche optimization]: https://rust-lang.github.io/unsafe-code-guidelines/layout/enums.html#discriminant-elision-on-option-like-enums
+/// [`Iffi`]: crate::Iffi
+/// [`MaybeInvalid<Self>`]: crate::MaybeInvalid
+/// [`unsafe_impl_nicheless!`]: crate::unsafe_impl_nicheless
 ///
 /// # Safety
 /// Type must have exactly one niche.
@@ -110,10 +190,6 @@ unsafe impl<E: OneNiche> Nicheless for Result<(), E> {}
 
 // SAFETY: only cannot be null
 unsafe impl<T> OneNiche for NonNull<T> {}
-// SAFETY: only cannot be null
-unsafe impl<'a, T> OneNiche for &'a T {}
-// SAFETY: only cannot be null
-unsafe impl<'a, T> OneNiche for &'a mut T {}
 
 macro_rules! impl_one_niche_bulk {
     ($($ty:ty),+$(,)?) => {
@@ -147,19 +223,86 @@ macro_rules! impl_oneniche_fn {
     };
 }
 
-// SAFETY: function pointers are non-null pointers.
-impl_oneniche_fn! {
-    {} T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14, T15, T16,
+macro_rules! impl_oneniche_fn_abis {
+    ($({ $($mod:tt)* }),+ $(,)?) => {
+        $(
+            impl_oneniche_fn! {
+                { $($mod)* }
+                T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14, T15,
+                T16, T17, T18, T19, T20, T21, T22, T23, T24, T25, T26, T27, T28, T29, T30, T31,
+            }
+        )+
+    };
 }
 
-impl_oneniche_fn! {
-    { unsafe } T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14, T15, T16,
+// SAFETY: function pointers are non-null pointers.
+impl_oneniche_fn_abis! {
+    {},
+    { unsafe },
+    { extern "C" },
+    { unsafe extern "C" },
+    { extern "C-unwind" },
+    { unsafe extern "C-unwind" },
+    { extern "system" },
+    { unsafe extern "system" },
+    { extern "system-unwind" },
+    { unsafe extern "system-unwind" },
 }
 
-impl_oneniche_fn! {
-    { extern "C" } T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14, T15, T16,
-}
+/// Implements [`Nicheless`] or [`OneNiche`] for types that cannot derive them,
+/// such as structs generated by `bindgen`.
+///
+/// Every impl must be justified with a `SAFETY` string, which is not otherwise used.
+/// As a sanity check, nicheless types are asserted at compile time to have no niche
+/// for `Option` to use, and one-niche types to have one.
+///
+/// The orphan rules still apply, so the types must be defined in the invoking crate.
+///
+/// # Example
+/// ```rust
+/// #[repr(C)]
+/// pub struct Header {
+///     pub len: u32,
+///     pub data: *const u8,
+/// }
+///
+/// #[repr(transparent)]
+/// pub struct Handle(core::num::NonZeroU32);
+///
+/// iffi::unsafe_impl_nicheless! {
+///     unsafe impl Nicheless for Header => "SAFETY: a `#[repr(C)]` struct of nicheless fields";
+///     unsafe impl OneNiche for Handle => "SAFETY: transparent over a one-niche type";
+/// }
+/// ```
+#[macro_export]
+macro_rules! unsafe_impl_nicheless {
+    () => {};
+    (unsafe impl Nicheless for $ty:ty => $safety:literal; $($rest:tt)*) => {
+        const _: () = {
+            let _: &str = $safety;
+            assert!(
+                ::core::mem::size_of::<::core::option::Option<$ty>>() > ::core::mem::size_of::<$ty>(),
+                concat!("`", stringify!($ty), "` has a niche so cannot be `Nicheless`"),
+            );
+        };
+
+        // SAFETY: upheld by the caller.
+        unsafe impl $crate::Nicheless for $ty {}
 
-impl_oneniche_fn! {
-    { unsafe extern "C" } T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14, T15, T16,
+        $crate::unsafe_impl_nicheless!($($rest)*);
+    };
+    (unsafe impl OneNiche for $ty:ty => $safety:literal; $($rest:tt)*) => {
+        const _: () = {
+            let _: &str = $safety;
+            assert!(
+                ::core::mem::size_of::<::core::option::Option<$ty>>() == ::core::mem::size_of::<$ty>(),
+                concat!("`", stringify!($ty), "` has no niche so cannot be `OneNiche`"),
+            );
+        };
+
+        // SAFETY: upheld by the caller.
+        unsafe impl $crate::OneNiche for $ty {}
+
+        $crate::unsafe_impl_nicheless!($($rest)*);
+    };
 }
@@ -1,4 +1,10 @@
-use core::{fmt, ops::RangeInclusive};
+use core::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    mem::size_of,
+    ops::RangeInclusive,
+};
 
 use bytemuck::Pod;
 
@@ -6,50 +12,266 @@ use bytemuck::Pod;
 ///
 /// See [the glossary entry] for more detail.
 ///
-/// Currently supports up to 20 bytes.
-/// If more space is needed, consider enabling the `"alloc"` feature.
+/// Supports up to [`CAPACITY`] bytes, 20 by default, and longer values are truncated.
+/// If more space is needed, enable one of the `"bit-pattern-32"`, `"bit-pattern-64"`,
+/// `"bit-pattern-128"` or `"bit-pattern-256"` features, the largest of which is used,
+/// or enable the `"alloc"` feature.
+/// Patterns and ranges are stored inline in every [`Error`],
+/// so a larger capacity makes errors larger too.
 ///
+/// [`Error`]: crate::Error
 /// [the glossary entry]: crate#bit-pattern
-#[derive(PartialEq, Eq, Debug, Clone)]
+/// [`CAPACITY`]: BitPattern::CAPACITY
+#[derive(PartialEq, Eq, Hash, Debug, Clone)]
 pub struct BitPattern {
-    bytes: [u8; 20],
-    len: usize,
+    bytes: [u8; BitPattern::CAPACITY],
+    len: u16,
+}
+
+/// Patterns are ordered lexicographically by their bytes in little-endian order.
+impl Ord for BitPattern {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_bytes().cmp(other.as_bytes())
+    }
+}
+
+impl PartialOrd for BitPattern {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
 }
 
 impl BitPattern {
+    /// The most bytes a pattern can hold.
+    pub const CAPACITY: usize = if cfg!(feature = "bit-pattern-256") {
+        256
+    } else if cfg!(feature = "bit-pattern-128") {
+        128
+    } else if cfg!(feature = "bit-pattern-64") {
+        64
+    } else if cfg!(feature = "bit-pattern-32") {
+        32
+    } else {
+        20
+    };
+
+    /// Creates a pattern from a value in native order, such as an integer,
+    /// stored as little-endian like every pattern.
+    ///
+    /// The same as [`from_ne`]: the bytes of the value are reversed on big-endian targets,
+    /// so bytes that are already in little-endian order should use [`from_le_bytes`].
+    /// Earlier versions had no other constructor, so the name is kept for compatibility.
+    ///
+    /// [`from_ne`]: BitPattern::from_ne
+    /// [`from_le_bytes`]: BitPattern::from_le_bytes
     pub fn from_le<T: Pod>(value: &T) -> Self {
-        let mut bytes = bytemuck::bytes_of(value);
-        let len = bytes.len().min(20);
-        #[allow(unused_mut)]
-        let mut buf = [0u8; 20];
+        Self::from_ne(value)
+    }
 
-        buf[..len].copy_from_slice(&bytes[..len]);
+    /// Creates a pattern from a value whose bytes are in big-endian order.
+    pub fn from_be<T: Pod>(value: &T) -> Self {
+        let mut pattern = Self::from_le_bytes(&[]);
+        // the least significant bytes are kept if the value is truncated.
+        for (slot, byte) in pattern
+            .bytes
+            .iter_mut()
+            .zip(bytemuck::bytes_of(value).iter().rev())
+        {
+            *slot = *byte;
+            pattern.len += 1;
+        }
+        pattern
+    }
 
-        #[cfg(target_endian = "big")]
-        buf[..len].reverse();
-        Self { bytes: buf, len }
+    /// Creates a pattern from a value whose bytes are in native order, such as an integer.
+    pub fn from_ne<T: Pod>(value: &T) -> Self {
+        if cfg!(target_endian = "big") {
+            Self::from_be(value)
+        } else {
+            Self::from_le_bytes(bytemuck::bytes_of(value))
+        }
     }
-}
 
-impl fmt::Display for BitPattern {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "0x")?;
-        for byte in self.bytes[0..self.len].iter().rev() {
-            write!(f, "{byte:02x}")?;
+    /// Creates a pattern from its bytes in little-endian order, truncated to the capacity.
+    pub fn from_le_bytes(bytes: &[u8]) -> Self {
+        let len = bytes.len().min(Self::CAPACITY);
+        let mut buf = [0u8; Self::CAPACITY];
+        buf[..len].copy_from_slice(&bytes[..len]);
+        Self {
+            bytes: buf,
+            len: len as u16,
         }
+    }
 
-        Ok(())
+    /// The bytes of the pattern in little-endian order.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.len as usize]
     }
 }
 
 /// A set of ranges of bit-patterns that are applicable for a type.
 ///
-/// Serves no purpose without the `"alloc"` feature.
-#[derive(PartialEq, Eq, Debug, Clone)]
-pub struct BitRanges(());
+/// Stores as many ranges as fit in [`CAPACITY`] bytes,
+/// which is always at least one range of the widest [`BitPattern`].
+/// Any further ranges are dropped and shown as `..`.
+///
+/// [`CAPACITY`]: BitRanges::CAPACITY
+#[derive(Debug, Clone)]
+pub struct BitRanges {
+    /// The start and end of each range, one after another.
+    bytes: [u8; BitRanges::CAPACITY],
+    // narrow fields keep errors small.
+    /// The number of bytes in the start and end of every range.
+    width: u16,
+    len: u16,
+    truncated: bool,
+}
+
+/// Ranges are equal if they contain the same ranges, whatever their width, as with [`Ord`].
+impl PartialEq for BitRanges {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for BitRanges {}
+
+impl Hash for BitRanges {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.ranges().for_each(|range| range.hash(state));
+        self.truncated.hash(state);
+    }
+}
+
+/// Ranges are ordered lexicographically by their starts and then their ends,
+/// with truncated ranges after those that are not.
+impl Ord for BitRanges {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let bounds = |range: RangeInclusive<BitPattern>| range.into_inner();
+        self.ranges()
+            .map(bounds)
+            .cmp(other.ranges().map(bounds))
+            .then(self.truncated.cmp(&other.truncated))
+    }
+}
+
+impl PartialOrd for BitRanges {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for BitRanges {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut list = f.debug_list();
+        for range in self.ranges() {
+            list.entry(&format_args!("{}..={}", range.start(), range.end()));
+        }
+        if self.truncated {
+            list.finish_non_exhaustive()
+        } else {
+            list.finish()
+        }
+    }
+}
 
 impl BitRanges {
-    pub fn from_le<T: Pod>(_: &[RangeInclusive<T>]) -> Self {
-        Self(())
+    /// The most bytes of ranges that can be stored.
+    pub const CAPACITY: usize = BitPattern::CAPACITY * 2;
+
+    fn empty(width: usize) -> Self {
+        Self {
+            bytes: [0u8; Self::CAPACITY],
+            width: width.min(BitPattern::CAPACITY) as u16,
+            len: 0,
+            truncated: false,
+        }
+    }
+
+    fn push(&mut self, range: RangeInclusive<BitPattern>) {
+        let (start, end) = (range.start().as_bytes(), range.end().as_bytes());
+        let width = self.width as usize;
+        let offset = self.len as usize * 2 * width;
+        match self.bytes.get_mut(offset..offset + 2 * width) {
+            Some(slot) if start.len() == width && end.len() == width => {
+                slot[..width].copy_from_slice(start);
+                slot[width..].copy_from_slice(end);
+                self.len += 1;
+            }
+            _ => self.truncated = true,
+        }
+    }
+
+    fn from_with<T: Pod>(value: &[RangeInclusive<T>], pattern: fn(&T) -> BitPattern) -> Self {
+        let mut ranges = Self::empty(size_of::<T>());
+        for range in value {
+            ranges.push(pattern(range.start())..=pattern(range.end()));
+        }
+        ranges
+    }
+
+    /// Creates ranges from values in native order, such as integers.
+    ///
+    /// The same as [`from_ne`], as with [`BitPattern::from_le`].
+    ///
+    /// [`from_ne`]: BitRanges::from_ne
+    pub fn from_le<T: Pod>(value: &[RangeInclusive<T>]) -> Self {
+        Self::from_with(value, BitPattern::from_le)
+    }
+
+    /// Creates ranges from values whose bytes are in big-endian order.
+    pub fn from_be<T: Pod>(value: &[RangeInclusive<T>]) -> Self {
+        Self::from_with(value, BitPattern::from_be)
+    }
+
+    /// Creates ranges from values whose bytes are in native order, such as integers.
+    pub fn from_ne<T: Pod>(value: &[RangeInclusive<T>]) -> Self {
+        Self::from_with(value, BitPattern::from_ne)
+    }
+
+    /// Collects a set of values, such as the discriminants of an enum,
+    /// into the fewest ranges that contain them.
+    ///
+    /// `succ` returns the value after another, if there is one.
+    pub fn from_ne_values<T: Pod + Ord>(values: &[T], succ: impl Fn(T) -> Option<T>) -> Self {
+        let mut ranges = Self::empty(size_of::<T>());
+        let mut prev: Option<T> = None;
+        // without a buffer to sort into, each range starts at the least value after the last.
+        while let Some(start) = values
+            .iter()
+            .copied()
+            .filter(|&value| prev.is_none_or(|prev| value > prev))
+            .min()
+        {
+            let mut end = start;
+            while let Some(next) = succ(end).filter(|next| values.contains(next)) {
+                end = next;
+            }
+            ranges.push(BitPattern::from_ne(&start)..=BitPattern::from_ne(&end));
+            prev = Some(end);
+        }
+        ranges
+    }
+
+    /// The ranges of bit-patterns.
+    pub(crate) fn ranges(&self) -> impl Iterator<Item = RangeInclusive<BitPattern>> + '_ {
+        let width = self.width as usize;
+        self.bytes[..self.len as usize * 2 * width]
+            .chunks_exact(2 * width.max(1))
+            .map(move |range| {
+                let (start, end) = range.split_at(width);
+                BitPattern::from_le_bytes(start)..=BitPattern::from_le_bytes(end)
+            })
+    }
+
+    #[cfg(feature = "serde")]
+    pub(crate) fn from_ranges(
+        ranges: impl IntoIterator<Item = RangeInclusive<BitPattern>>,
+    ) -> Self {
+        let mut ranges = ranges.into_iter().peekable();
+        let width = ranges.peek().map_or(0, |range| range.start().len());
+        let mut this = Self::empty(width);
+        ranges.for_each(|range| this.push(range));
+        this
     }
 }
@@ -0,0 +1,36 @@
+use rayon::prelude::*;
+
+use crate::{Error, Iffi, Nicheless};
+
+/// Validates every element of a slice of FFI-safe [nicheless] values in parallel.
+///
+/// If several elements are invalid, the error for the one with the lowest index is returned.
+///
+/// [nicheless]: Nicheless
+pub fn par_validate<T: Iffi<U>, U: Nicheless + Sync>(values: &[U]) -> Result<(), Error> {
+    if T::TRIVIAL_CHECK {
+        return Ok(());
+    }
+
+    // `Error` is not `Send`, so only the index of the first invalid element crosses threads.
+    match values
+        .par_iter()
+        .position_first(|value| T::can_transmute(value).is_err())
+    {
+        Some(index) => T::can_transmute(&values[index]).map_err(|e| e.at_index(index)),
+        None => Ok(()),
+    }
+}
+
+/// Tries to convert a slice of FFI-safe [nicheless] values to a slice of ergonomic ones,
+/// validating the elements in parallel.
+///
+/// See [`try_from_slice`] for the single-threaded equivalent.
+///
+/// [nicheless]: Nicheless
+/// [`try_from_slice`]: crate::try_from_slice
+pub fn par_try_from_slice<T: Iffi<U>, U: Nicheless + Sync>(values: &[U]) -> Result<&[T], Error> {
+    par_validate::<T, U>(values)?;
+    // SAFETY: T and U have the same layout and every element is safe to transmute.
+    unsafe { Ok(&*(values as *const [U] as *const [T])) }
+}
@@ -0,0 +1,107 @@
+use bytemuck::{AnyBitPattern, CheckedBitPattern, Pod};
+
+use crate::{BitPattern, BitRanges, Error, ErrorKind, Iffi, MaybeInvalid, Nicheless};
+
+/// Adapts a type implementing [`bytemuck::AnyBitPattern`] into a [nicheless] universe,
+/// so that types already deriving `bytemuck` traits need not also derive [`Nicheless`].
+///
+/// Every `T: AnyBitPattern` implements `Iffi<PodUniverse<T>>`,
+/// so fields of such types can be validated with `#[iffi(with = PodUniverse<T>)]`.
+///
+/// `T` should be `#[repr(C)]` or `#[repr(transparent)]`,
+/// as `AnyBitPattern` alone does not guarantee an FFI-safe layout.
+///
+/// # Example
+/// ```rust
+/// # use core::num::NonZeroU32;
+/// # use iffi::{Iffi, PodUniverse};
+/// #[derive(Clone, Copy, bytemuck::Zeroable, bytemuck::Pod)]
+/// #[repr(C)]
+/// struct Extent {
+///     width: u32,
+///     height: u32,
+/// }
+///
+/// #[derive(Iffi)]
+/// #[repr(C)]
+/// struct Image {
+///     id: NonZeroU32,
+///     #[iffi(with = PodUniverse<Extent>)]
+///     extent: Extent,
+/// }
+/// ```
+///
+/// [nicheless]: Nicheless
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct PodUniverse<T>(pub T);
+
+// SAFETY: `AnyBitPattern` types are valid for any bit-pattern.
+unsafe impl<T: AnyBitPattern> Nicheless for PodUniverse<T> {}
+
+// SAFETY: `PodUniverse<T>` is `#[repr(transparent)]` over `T`.
+unsafe impl<T: AnyBitPattern> Iffi<PodUniverse<T>> for T {
+    const TRIVIAL_CHECK: bool = true;
+
+    fn can_transmute(_: &PodUniverse<T>) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// Adapts a type implementing [`bytemuck::CheckedBitPattern`] to implement [`Iffi`],
+/// validating it with [`CheckedBitPattern::is_valid_bit_pattern`].
+///
+/// `Checked<T>` implements `Iffi` over [`PodUniverse<T::Bits>`](PodUniverse)
+/// and over [`MaybeInvalid<Self>`].
+/// `T::Bits` must be [`Pod`](bytemuck::Pod) so that the invalid bits can be reported
+/// without reading padding bytes.
+/// As `CheckedBitPattern` cannot describe which bit-patterns are valid,
+/// the `valid` ranges of its errors are empty.
+///
+/// # Example
+/// ```rust
+/// # use iffi::{Checked, PodUniverse};
+/// let valid: Checked<char> = iffi::try_from(PodUniverse(0x61u32)).unwrap();
+/// assert_eq!(valid.0, 'a');
+/// assert!(iffi::try_from::<Checked<char>, _>(PodUniverse(0xd800u32)).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[repr(transparent)]
+pub struct Checked<T>(pub T);
+
+fn check_bits<T: CheckedBitPattern, U: Nicheless>(bits: &T::Bits) -> Result<(), Error>
+where
+    T::Bits: Pod,
+    Checked<T>: Iffi<U>,
+{
+    if T::is_valid_bit_pattern(bits) {
+        return Ok(());
+    }
+    Err(Error::new::<Checked<T>, U>(ErrorKind::InvalidBitPattern {
+        bits: BitPattern::from_ne(bits),
+        valid: BitRanges::from_ne::<u8>(&[]),
+    }))
+}
+
+// SAFETY: `CheckedBitPattern` guarantees that `T` has the same layout as `T::Bits`
+// and that the bits are a valid `T` if `is_valid_bit_pattern` returns `true`.
+unsafe impl<T: CheckedBitPattern> Iffi<PodUniverse<T::Bits>> for Checked<T>
+where
+    T::Bits: Pod,
+{
+    fn can_transmute(superset: &PodUniverse<T::Bits>) -> Result<(), Error> {
+        check_bits::<T, PodUniverse<T::Bits>>(&superset.0)
+    }
+}
+
+// SAFETY: as above.
+unsafe impl<T: CheckedBitPattern> Iffi<MaybeInvalid<Self>> for Checked<T>
+where
+    T::Bits: Pod,
+{
+    fn can_transmute(superset: &MaybeInvalid<Self>) -> Result<(), Error> {
+        // SAFETY: `T::Bits` has the same layout as `T` and is valid for any bit-pattern.
+        let bits = unsafe { &*(superset.as_ptr() as *const T::Bits) };
+        check_bits::<T, MaybeInvalid<Self>>(bits)
+    }
+}
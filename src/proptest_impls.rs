@@ -0,0 +1,75 @@
+use core::{fmt::Debug, mem::size_of};
+
+use std::vec::Vec;
+
+use proptest::{collection::vec, prelude::*};
+
+use crate::{MaybeInvalid, NoPadding};
+
+/// Bytes that are as likely to be one of the values at the edges of a niche,
+/// like `0x00`, `0x01`, `0x7f`, `0x80` and `0xff`, as to be any other value.
+fn edge_byte() -> impl Strategy<Value = u8> {
+    prop_oneof![
+        any::<u8>(),
+        prop::sample::select(&[0x00, 0x01, 0x7f, 0x80, 0xff][..]),
+    ]
+}
+
+fn from_bytes<T>(bytes: Vec<u8>) -> MaybeInvalid<T> {
+    let mut value = MaybeInvalid::zeroed();
+    // SAFETY: the value is zeroed, so every byte is initialized.
+    unsafe { value.as_bytes_mut_unchecked() }.copy_from_slice(&bytes);
+    value
+}
+
+/// A [`Strategy`] generating potentially invalid values of `T` made of arbitrary bytes,
+/// biased towards the bytes at the edges of common niches.
+///
+/// # Example
+/// ```rust
+/// # use core::num::NonZeroU16;
+/// use proptest::prelude::*;
+///
+/// proptest!(|(value in iffi::maybe_invalid_strategy::<NonZeroU16>())| {
+///     let raw = u16::from_ne_bytes(value.as_bytes().try_into().unwrap());
+///     prop_assert_eq!(iffi::try_from::<NonZeroU16, _>(value).is_ok(), raw != 0);
+/// });
+/// ```
+pub fn maybe_invalid_strategy<T>() -> impl Strategy<Value = MaybeInvalid<T>> {
+    vec(edge_byte(), size_of::<T>()).prop_map(from_bytes)
+}
+
+/// A [`Strategy`] generating potentially invalid values of `T`
+/// by flipping one or two bits of the valid values generated by `valid`,
+/// to probe the boundaries between valid and invalid bit-patterns.
+///
+/// `T` must have [no padding], as any bit may be flipped.
+///
+/// # Example
+/// ```rust
+/// # use core::num::NonZeroU8;
+/// use proptest::prelude::*;
+///
+/// let valid = (1u8..).prop_map(|n| NonZeroU8::new(n).unwrap());
+/// proptest!(|(value in iffi::near_valid_strategy(valid))| {
+///     if let Ok(n) = iffi::try_from::<NonZeroU8, _>(value) {
+///         prop_assert_ne!(n.get(), 0);
+///     }
+/// });
+/// ```
+///
+/// [no padding]: NoPadding
+pub fn near_valid_strategy<T: NoPadding + Debug>(
+    valid: impl Strategy<Value = T>,
+) -> impl Strategy<Value = MaybeInvalid<T>> {
+    let bits = size_of::<T>() * 8;
+    (valid, vec(0..bits.max(1), 1..=2)).prop_map(move |(valid, flips)| {
+        let mut value = MaybeInvalid::new(valid);
+        if bits > 0 {
+            for bit in flips {
+                value.as_bytes_mut()[bit / 8] ^= 1 << (bit % 8);
+            }
+        }
+        value
+    })
+}
@@ -0,0 +1,159 @@
+use core::{fmt, marker::PhantomData};
+
+use serde::{
+    de::{self, SeqAccess, Visitor},
+    ser::SerializeStruct,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::{BitPattern, BitRanges, FieldPath, Iffi, Nicheless};
+
+/// Bit-patterns are serialized as their bytes in little-endian order.
+impl Serialize for BitPattern {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self.as_bytes())
+    }
+}
+
+struct BitPatternVisitor;
+
+impl<'de> Visitor<'de> for BitPatternVisitor {
+    type Value = BitPattern;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("the little-endian bytes of a bit-pattern")
+    }
+
+    fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<Self::Value, E> {
+        Ok(BitPattern::from_le_bytes(bytes))
+    }
+
+    #[cfg(feature = "alloc")]
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut bytes = alloc::vec::Vec::new();
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+
+        Ok(BitPattern::from_le_bytes(&bytes))
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut bytes = [0u8; BitPattern::CAPACITY];
+        let mut len = 0;
+        while let Some(byte) = seq.next_element()? {
+            let Some(slot) = bytes.get_mut(len) else {
+                return Err(de::Error::invalid_length(len + 1, &self));
+            };
+            *slot = byte;
+            len += 1;
+        }
+
+        Ok(BitPattern::from_le_bytes(&bytes[..len]))
+    }
+}
+
+impl<'de> Deserialize<'de> for BitPattern {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_bytes(BitPatternVisitor)
+    }
+}
+
+/// Bit-ranges are serialized as a sequence of inclusive ranges.
+impl Serialize for BitRanges {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.ranges())
+    }
+}
+
+struct BitRangesVisitor;
+
+impl<'de> Visitor<'de> for BitRangesVisitor {
+    type Value = BitRanges;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a sequence of inclusive ranges of bit-patterns")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut error = None;
+        let ranges = BitRanges::from_ranges(core::iter::from_fn(|| {
+            seq.next_element().unwrap_or_else(|e| {
+                error = Some(e);
+                None
+            })
+        }));
+
+        error.map_or(Ok(ranges), Err)
+    }
+}
+
+impl<'de> Deserialize<'de> for BitRanges {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_seq(BitRangesVisitor)
+    }
+}
+
+impl Serialize for FieldPath {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut path = serializer.serialize_struct("FieldPath", 2)?;
+        path.serialize_field("ty", &self.ty())?;
+        path.serialize_field("fields", self.fields())?;
+        path.end()
+    }
+}
+
+/// Serializes a value by its [`Display`](fmt::Display) implementation.
+#[cfg(feature = "alloc")]
+pub(crate) fn display<T: fmt::Display + ?Sized, S: Serializer>(
+    value: &T,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(value)
+}
+
+/// Serializes a value as its [universe] `U` and validates it when deserializing,
+/// so that values read from a serialized format are checked like those from foreign code.
+///
+/// # Example
+/// ```rust
+/// # use core::num::NonZeroU16;
+/// use iffi::ViaUniverse;
+///
+/// let port: ViaUniverse<NonZeroU16, u16> = serde_json::from_str("8080").unwrap();
+/// assert_eq!(port.0.get(), 8080);
+/// assert_eq!(serde_json::to_string(&port).unwrap(), "8080");
+/// assert!(serde_json::from_str::<ViaUniverse<NonZeroU16, u16>>("0").is_err());
+/// ```
+///
+/// [universe]: crate#universe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ViaUniverse<T, U>(pub T, PhantomData<fn() -> U>);
+
+impl<T: Iffi<U>, U: Nicheless> ViaUniverse<T, U> {
+    /// Wraps a value to be serialized as its universe `U`.
+    pub fn new(value: T) -> Self {
+        Self(value, PhantomData)
+    }
+
+    /// Unwraps the value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T: Iffi<U>, U: Nicheless + Serialize> Serialize for ViaUniverse<T, U> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        crate::into_ref::<T, U>(&self.0).serialize(serializer)
+    }
+}
+
+impl<'de, T: Iffi<U>, U: Nicheless + Deserialize<'de>> Deserialize<'de> for ViaUniverse<T, U> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let universe = U::deserialize(deserializer)?;
+        crate::try_from(universe)
+            .map(Self::new)
+            .map_err(de::Error::custom)
+    }
+}
@@ -0,0 +1,7 @@
+use uuid::Uuid;
+
+use crate::{Nicheless, NoPadding};
+
+// SAFETY: `Uuid` is `#[repr(transparent)]` over `[u8; 16]`.
+unsafe impl Nicheless for Uuid {}
+unsafe impl NoPadding for Uuid {}
@@ -0,0 +1,80 @@
+use core::{any::type_name, ffi::c_void, ptr::NonNull};
+
+use windows_sys::core::{BOOL, GUID};
+
+use crate::{Error, Iffi, MaybeInvalid, Nicheless, NoPadding, OneNiche};
+
+// SAFETY: a `#[repr(C)]` struct of integers with no padding.
+unsafe impl Nicheless for GUID {}
+unsafe impl NoPadding for GUID {}
+
+/// A Windows `BOOL` that is exactly `FALSE` or `TRUE`.
+///
+/// Validated from a [`BOOL`], rejecting every other value.
+/// Many functions document only that they return a nonzero value on success,
+/// so compare their results with zero instead.
+///
+/// [`BOOL`]: windows_sys::core::BOOL
+#[derive(Iffi, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[iffi(crate = "crate")]
+#[repr(i32)]
+pub enum WinBool {
+    False = 0,
+    True = 1,
+}
+
+impl From<bool> for WinBool {
+    fn from(value: bool) -> Self {
+        if value {
+            Self::True
+        } else {
+            Self::False
+        }
+    }
+}
+
+impl From<WinBool> for bool {
+    fn from(value: WinBool) -> Self {
+        value == WinBool::True
+    }
+}
+
+// SAFETY: `WinBool` is `#[repr(i32)]` like `BOOL`, and is checked as its own universe.
+unsafe impl Iffi<BOOL> for WinBool {
+    fn can_transmute(superset: &BOOL) -> Result<(), Error> {
+        // SAFETY: `MaybeInvalid<WinBool>` has the same layout as `BOOL`.
+        let value = unsafe { &*(superset as *const BOOL as *const MaybeInvalid<Self>) };
+        <Self as Iffi>::can_transmute(value).map_err(|error| Error {
+            from: type_name::<BOOL>(),
+            ..error
+        })
+    }
+}
+
+/// A Windows `HANDLE`, `HWND` or other handle that is not null.
+///
+/// Validated from the raw handle, rejecting null.
+/// `Option<WinHandle>` is nicheless and is `None` for null handles.
+/// Functions that return `INVALID_HANDLE_VALUE` on failure should be checked separately.
+#[derive(Iffi, OneNiche, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[iffi(crate = "crate")]
+#[repr(transparent)]
+#[one_niche(value = 0)]
+pub struct WinHandle(NonNull<c_void>);
+
+impl WinHandle {
+    /// The raw handle.
+    pub fn as_raw(self) -> *mut c_void {
+        self.0.as_ptr()
+    }
+}
+
+// SAFETY: `WinHandle` is `#[repr(transparent)]` over `NonNull<c_void>`.
+unsafe impl Iffi<*mut c_void> for WinHandle {
+    fn can_transmute(superset: &*mut c_void) -> Result<(), Error> {
+        <NonNull<c_void> as Iffi<*mut c_void>>::can_transmute(superset).map_err(|error| Error {
+            into: type_name::<Self>(),
+            ..error
+        })
+    }
+}